@@ -1,9 +1,16 @@
-use std::path::PathBuf;
+use std::{fmt::Display, path::PathBuf};
 
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand};
 use macroquad::{prelude::*, ui::root_ui};
 
-use cozynes::{bus::Bus, cpu::Cpu, mem::Mem, rom::Rom};
+use cozynes::{
+    bus::Bus,
+    cpu::{Cpu, StopReason},
+    crash,
+    mem::Mem,
+    rom::Rom,
+    trace::{self, TraceFormat},
+};
 
 fn window_conf() -> Conf {
     Conf {
@@ -18,6 +25,8 @@ fn window_conf() -> Conf {
 
 const SCREEN_WIDTH: usize = 32;
 const SCREEN_HEIGHT: usize = 32;
+/// Roughly the 200 instructions per frame the snake demo was tuned for.
+const CYCLES_PER_FRAME: usize = 700;
 
 fn color(byte: u8) -> [u8; 4] {
     match byte {
@@ -46,7 +55,20 @@ fn read_screen_state(cpu: &Cpu, frame: &mut [u8]) -> bool {
     update
 }
 
-fn handle_input(cpu: &mut Cpu) {
+/// Writes a crash dump for bug reports to the working directory.
+fn write_crash_dump(cpu: &Cpu, reason: impl Display) {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or_default();
+    let path = PathBuf::from(format!("cozynes-crash-{}.txt", secs));
+    match crash::write(cpu, reason, &path) {
+        Ok(()) => log::error!("crash dump written to {}", path.display()),
+        Err(error) => log::error!("failed to write crash dump: {}", error),
+    }
+}
+
+fn handle_input(cpu: &mut Cpu, stopped: &mut bool) {
     if is_key_pressed(KeyCode::Escape) {
         std::process::exit(0);
     }
@@ -64,31 +86,145 @@ fn handle_input(cpu: &mut Cpu) {
     }
     if is_key_pressed(KeyCode::R) {
         cpu.reset();
-        cpu.running = true;
+        *stopped = false;
     }
 }
 
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None)]
+#[clap(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Cli {
-    rom: PathBuf,
+    #[clap(subcommand)]
+    command: Option<Command>,
+    rom: Option<PathBuf>,
     #[clap(long, help = "Enable tracing")]
     trace: bool,
 }
 
-#[macroquad::main(window_conf)]
-async fn main() {
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run a ROM without a window and compare its trace against a reference log
+    DiffTrace {
+        rom: PathBuf,
+        log: PathBuf,
+        /// Start at this address instead of the reset vector, e.g. C000 for nestest
+        #[clap(long, value_parser = parse_hex)]
+        pc: Option<u16>,
+        #[clap(long, default_value_t = 5, help = "Lines of context to print")]
+        context: usize,
+    },
+}
+
+fn parse_hex(s: &str) -> Result<u16, std::num::ParseIntError> {
+    u16::from_str_radix(s.trim_start_matches('$').trim_start_matches("0x"), 16)
+}
+
+fn diff_trace(rom: PathBuf, log: PathBuf, pc: Option<u16>, context: usize) -> i32 {
+    let rom = match std::fs::read(&rom) {
+        Ok(file) => file,
+        Err(error) => {
+            eprintln!("cannot read {}: {}", rom.display(), error);
+            return 1;
+        }
+    };
+    let rom = match Rom::new(&rom) {
+        Ok(rom) => rom,
+        Err(error) => {
+            eprintln!("cannot load ROM: {}", error);
+            return 1;
+        }
+    };
+    let reference = match std::fs::read_to_string(&log) {
+        Ok(reference) => reference,
+        Err(error) => {
+            eprintln!("cannot read {}: {}", log.display(), error);
+            return 1;
+        }
+    };
+    let format = TraceFormat::detect(reference.lines().next().unwrap_or(""));
+
+    let bus = match Bus::new(rom) {
+        Ok(bus) => bus,
+        Err(error) => {
+            eprintln!("cannot load ROM: {}", error);
+            return 1;
+        }
+    };
+    let mut cpu = Cpu::new(bus);
+    if let Some(pc) = pc {
+        cpu.pc = pc;
+    }
+
+    match trace::diff(&mut cpu, &reference, format, context) {
+        Ok(None) => {
+            println!(
+                "trace matches all {} reference lines",
+                reference.lines().count()
+            );
+            0
+        }
+        Ok(Some(divergence)) => {
+            eprintln!("trace diverges at line {}:", divergence.line);
+            eprint!("{}", divergence);
+            1
+        }
+        Err(error) => {
+            eprintln!("CPU halted: {}", error);
+            1
+        }
+    }
+}
+
+fn main() {
     pretty_env_logger::init();
 
     let cli = Cli::parse();
 
-    // let file = std::fs::read("/home/luka/code/nes/nestest.nes").unwrap();
-    let file = std::fs::read(cli.rom).unwrap();
-    let rom = Rom::new(&file).unwrap();
-    let bus = Bus::new(rom);
+    match cli.command {
+        Some(Command::DiffTrace {
+            rom,
+            log,
+            pc,
+            context,
+        }) => std::process::exit(diff_trace(rom, log, pc, context)),
+        None => {
+            let Some(rom) = cli.rom else {
+                Cli::command()
+                    .error(
+                        clap::error::ErrorKind::MissingRequiredArgument,
+                        "a ROM to run is required",
+                    )
+                    .exit();
+            };
+            macroquad::Window::from_config(window_conf(), run(rom, cli.trace));
+        }
+    }
+}
+
+async fn run(rom: PathBuf, tracing: bool) {
+    let file = match std::fs::read(&rom) {
+        Ok(file) => file,
+        Err(error) => {
+            eprintln!("cannot read {}: {}", rom.display(), error);
+            std::process::exit(1);
+        }
+    };
+    let rom = match Rom::new(&file) {
+        Ok(rom) => rom,
+        Err(error) => {
+            eprintln!("cannot load ROM: {}", error);
+            std::process::exit(1);
+        }
+    };
+    let bus = match Bus::new(rom) {
+        Ok(bus) => bus,
+        Err(error) => {
+            eprintln!("cannot load ROM: {}", error);
+            std::process::exit(1);
+        }
+    };
     let mut cpu = Cpu::new(bus);
-    cpu.pc = 0xC000;
-    cpu.running = true;
+    let mut stopped = false;
+    let mut overshoot = 0;
 
     rand::srand(std::time::Instant::now().elapsed().as_millis() as u64);
 
@@ -115,15 +251,34 @@ async fn main() {
                 ..Default::default()
             },
         );
-        for _ in 0..200 {
-            let random = rand::gen_range(1, 16);
-            handle_input(&mut cpu);
-            if cpu.running {
-                cpu.write_byte(0xFE, random);
-                if cli.trace {
-                    println!("{}", cozynes::trace::trace(&cpu));
+        handle_input(&mut cpu, &mut stopped);
+        if !stopped {
+            let budget = CYCLES_PER_FRAME.saturating_sub(overshoot);
+            let result = cpu.run_for_cycles_with_callback(budget, |cpu| {
+                cpu.write_byte(0xFE, rand::gen_range(1, 16));
+                if tracing {
+                    match trace::trace(cpu) {
+                        Ok(line) => println!("{}", line),
+                        Err(error) => log::error!("{}", error),
+                    }
+                }
+            });
+            match result {
+                Ok(StopReason::CycleBudgetExhausted { overshoot: o }) => overshoot = o,
+                Ok(StopReason::Jammed(jam)) => {
+                    log::error!("CPU jammed: {:?}", jam);
+                    write_crash_dump(&cpu, format!("jammed: {:?}", jam));
+                    stopped = true;
+                }
+                Ok(reason) => {
+                    log::info!("CPU stopped: {:?}", reason);
+                    stopped = true;
+                }
+                Err(error) => {
+                    log::error!("CPU halted: {}", error);
+                    write_crash_dump(&cpu, error);
+                    stopped = true;
                 }
-                cpu.step();
             }
         }
         next_frame().await;
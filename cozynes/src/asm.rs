@@ -0,0 +1,837 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    cpu::{AddressingMode, Variant},
+    instruction::Instruction,
+};
+
+/// What went wrong while assembling a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnknownMnemonic(String),
+    UnknownDirective(String),
+    InvalidOperand(String),
+    /// The mnemonic exists, but not with the addressing mode used.
+    InvalidAddressingMode(String),
+    UndefinedLabel(String),
+    DuplicateLabel(String),
+    BranchOutOfRange(i64),
+    ValueOutOfRange(i64),
+    /// A `.org` that would move back over already assembled code.
+    OrgBackwards(u16),
+}
+
+/// An assembler error and the line, counting from 1, it occurred on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub line: usize,
+    pub kind: ErrorKind,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ErrorKind::UnknownMnemonic(mnemonic) => write!(f, "unknown mnemonic {}", mnemonic),
+            ErrorKind::UnknownDirective(directive) => {
+                write!(f, "unknown directive {}", directive)
+            }
+            ErrorKind::InvalidOperand(operand) => write!(f, "invalid operand {}", operand),
+            ErrorKind::InvalidAddressingMode(mnemonic) => {
+                write!(f, "addressing mode not supported by {}", mnemonic)
+            }
+            ErrorKind::UndefinedLabel(label) => write!(f, "undefined label {}", label),
+            ErrorKind::DuplicateLabel(label) => write!(f, "label {} defined twice", label),
+            ErrorKind::BranchOutOfRange(offset) => {
+                write!(f, "branch offset {} out of range", offset)
+            }
+            ErrorKind::ValueOutOfRange(value) => write!(f, "value {} out of range", value),
+            ErrorKind::OrgBackwards(addr) => write!(f, ".org ${:04x} moves backwards", addr),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The output of `assemble`.
+#[derive(Debug)]
+pub struct Program {
+    /// The address of the first byte.
+    pub origin: u16,
+    pub bytes: Vec<u8>,
+    pub labels: BTreeMap<String, u16>,
+}
+
+/// Alternative names other assemblers use for the unofficial opcodes.
+const ALIASES: [(&str, &str); 9] = [
+    ("ISC", "ISB"),
+    ("SBX", "AXS"),
+    ("ASR", "ALR"),
+    ("ANE", "XAA"),
+    ("SHA", "AHX"),
+    ("SHS", "TAS"),
+    ("LAR", "LAS"),
+    ("KIL", "JAM"),
+    ("HLT", "JAM"),
+];
+
+#[derive(Debug)]
+enum Index {
+    X,
+    Y,
+}
+
+#[derive(Debug)]
+enum Operand<'a> {
+    None,
+    Immediate(&'a str),
+    Direct {
+        expr: &'a str,
+        index: Option<Index>,
+        /// Set by an `a:` prefix, which keeps small values from using zero page.
+        absolute: bool,
+    },
+    /// `(zp,X)` or `(abs,X)`
+    IndexedIndirect(&'a str),
+    /// `(zp),Y`
+    IndirectIndexed(&'a str),
+    /// `(zp)` or `(abs)`
+    Indirect(&'a str),
+    /// `zp,target`, used by BBR and BBS
+    ZeroPageRelative(&'a str, &'a str),
+}
+
+#[derive(Debug)]
+enum Item<'a> {
+    Expr(&'a str),
+    Str(&'a str),
+}
+
+#[derive(Debug)]
+enum Statement<'a> {
+    Label(&'a str),
+    Constant(&'a str, &'a str),
+    Org(&'a str),
+    Bytes(Vec<Item<'a>>),
+    Words(Vec<&'a str>),
+    Instruction {
+        mnemonic: String,
+        operand: Operand<'a>,
+    },
+}
+
+/// Assembles `source` with the NMOS opcode table. Code starts at `origin`
+/// unless the source begins with a `.org`, gaps left by later `.org`s are
+/// filled with zeros.
+///
+/// Labels are defined with `name:` and constants with `name = value`.
+/// Numbers are written as `$ff`, `%1010` or decimal, and `<` and `>` take
+/// the low and high byte of an expression. An `a:` prefix forces absolute
+/// addressing for operands that would fit in zero page.
+pub fn assemble(source: &str, origin: u16) -> Result<Program, Error> {
+    assemble_for(Variant::default(), source, origin)
+}
+
+/// Like `assemble`, but with the opcode table of the given CPU variant.
+pub fn assemble_for(variant: Variant, source: &str, origin: u16) -> Result<Program, Error> {
+    let mut statements = Vec::new();
+    for (number, line) in source.lines().enumerate() {
+        parse_line(line, number + 1, &mut statements).map_err(|kind| Error {
+            line: number + 1,
+            kind,
+        })?;
+    }
+
+    let table = variant.instructions();
+
+    // the first pass picks the opcode of every instruction, which fixes the
+    // address of every label
+    let mut labels = BTreeMap::new();
+    let mut opcodes = Vec::new();
+    let mut pc = origin;
+    let mut start = None;
+    for (line, statement) in &statements {
+        let error = |kind| Error { line: *line, kind };
+        match statement {
+            Statement::Label(name) => define(&mut labels, name, pc).map_err(error)?,
+            Statement::Constant(name, expr) => {
+                let value = resolve(expr, &labels, pc).map_err(error)?;
+                define(&mut labels, name, word(value).map_err(error)?).map_err(error)?;
+            }
+            Statement::Org(expr) => {
+                let addr = word(resolve(expr, &labels, pc).map_err(error)?).map_err(error)?;
+                if start.is_some() && addr < pc {
+                    return Err(error(ErrorKind::OrgBackwards(addr)));
+                }
+                pc = addr;
+            }
+            Statement::Bytes(items) => {
+                let len: usize = items
+                    .iter()
+                    .map(|item| match item {
+                        Item::Expr(_) => 1,
+                        Item::Str(s) => s.len(),
+                    })
+                    .sum();
+                start.get_or_insert(pc);
+                pc = pc.wrapping_add(len as u16);
+            }
+            Statement::Words(exprs) => {
+                start.get_or_insert(pc);
+                pc = pc.wrapping_add(2 * exprs.len() as u16);
+            }
+            Statement::Instruction { mnemonic, operand } => {
+                let ins = select(table, mnemonic, operand, &labels, pc).map_err(error)?;
+                opcodes.push(ins);
+                start.get_or_insert(pc);
+                pc = pc.wrapping_add(ins.bytes as u16);
+            }
+        }
+    }
+
+    let origin = start.unwrap_or(pc);
+    let mut bytes = Vec::new();
+    let mut opcodes = opcodes.into_iter();
+    let mut pc = origin;
+    let labels = &labels;
+    for (line, statement) in &statements {
+        let error = |kind| Error { line: *line, kind };
+        let value = move |expr: &str| resolve(expr, labels, pc).map_err(error);
+        let mut out = Vec::new();
+        match statement {
+            Statement::Label(_) | Statement::Constant(..) => (),
+            Statement::Org(expr) => {
+                let addr = value(expr)? as u16;
+                if bytes.is_empty() {
+                    pc = addr;
+                } else {
+                    out.resize(addr.wrapping_sub(pc) as usize, 0);
+                }
+            }
+            Statement::Bytes(items) => {
+                for item in items {
+                    match item {
+                        Item::Expr(expr) => out.push(byte(value(expr)?).map_err(error)?),
+                        Item::Str(s) => out.extend_from_slice(s.as_bytes()),
+                    }
+                }
+            }
+            Statement::Words(exprs) => {
+                for expr in exprs {
+                    out.extend_from_slice(&word(value(expr)?).map_err(error)?.to_le_bytes());
+                }
+            }
+            Statement::Instruction { operand, .. } => {
+                let ins = opcodes.next().unwrap();
+                out.push(ins.opcode);
+                encode(ins, operand, labels, pc, &mut out).map_err(error)?;
+            }
+        }
+        pc = pc.wrapping_add(out.len() as u16);
+        bytes.extend(out);
+    }
+
+    Ok(Program {
+        origin,
+        bytes,
+        labels: labels.clone(),
+    })
+}
+
+fn define(labels: &mut BTreeMap<String, u16>, name: &str, value: u16) -> Result<(), ErrorKind> {
+    match labels.insert(name.to_string(), value) {
+        Some(_) => Err(ErrorKind::DuplicateLabel(name.to_string())),
+        None => Ok(()),
+    }
+}
+
+fn parse_line<'a>(
+    line: &'a str,
+    number: usize,
+    statements: &mut Vec<(usize, Statement<'a>)>,
+) -> Result<(), ErrorKind> {
+    let mut rest = strip_comment(line).trim();
+
+    while let Some((label, after)) = rest.split_once(':') {
+        if !is_identifier(label) {
+            break;
+        }
+        statements.push((number, Statement::Label(label)));
+        rest = after.trim();
+    }
+    if rest.is_empty() {
+        return Ok(());
+    }
+
+    if let Some((name, expr)) = rest.split_once('=') {
+        if is_identifier(name.trim()) {
+            statements.push((number, Statement::Constant(name.trim(), expr.trim())));
+            return Ok(());
+        }
+    }
+
+    let (word, args) = match rest.split_once(char::is_whitespace) {
+        Some((word, args)) => (word, args.trim()),
+        None => (rest, ""),
+    };
+
+    let statement = if word.starts_with('.') {
+        match word.to_ascii_lowercase().as_str() {
+            ".org" => Statement::Org(args),
+            ".byte" | ".db" => Statement::Bytes(
+                split_args(args)
+                    .into_iter()
+                    .map(|arg| match arg.strip_prefix('"') {
+                        Some(s) => s
+                            .strip_suffix('"')
+                            .map(Item::Str)
+                            .ok_or_else(|| ErrorKind::InvalidOperand(arg.to_string())),
+                        None => Ok(Item::Expr(arg)),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            ".word" | ".dw" | ".addr" => Statement::Words(split_args(args)),
+            _ => return Err(ErrorKind::UnknownDirective(word.to_string())),
+        }
+    } else {
+        let mut mnemonic = word.to_ascii_uppercase();
+        if let Some((_, name)) = ALIASES.iter().find(|(alias, _)| *alias == mnemonic) {
+            mnemonic = name.to_string();
+        }
+        Statement::Instruction {
+            mnemonic,
+            operand: parse_operand(args)?,
+        }
+    };
+    statements.push((number, statement));
+    Ok(())
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => return &line[..i],
+            _ => (),
+        }
+    }
+    line
+}
+
+fn split_args(args: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in args.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                parts.push(args[start..i].trim());
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    parts.push(args[start..].trim());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// Matches the `,X` or `,Y` that ends an `(zp),Y` operand.
+fn is_index(s: &str, register: &str) -> bool {
+    s.strip_prefix(',')
+        .is_some_and(|index| index.trim().eq_ignore_ascii_case(register))
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '@')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse_operand(operand: &str) -> Result<Operand<'_>, ErrorKind> {
+    let operand = operand.trim();
+    let invalid = || ErrorKind::InvalidOperand(operand.to_string());
+
+    if operand.is_empty() || operand.eq_ignore_ascii_case("a") {
+        return Ok(Operand::None);
+    }
+    if let Some(expr) = operand.strip_prefix('#') {
+        return Ok(Operand::Immediate(expr.trim()));
+    }
+    if let Some(inner) = operand.strip_prefix('(') {
+        if let Some(inner) = inner.strip_suffix(')') {
+            return match inner.rsplit_once(',') {
+                Some((expr, index)) if index.trim().eq_ignore_ascii_case("x") => {
+                    Ok(Operand::IndexedIndirect(expr.trim()))
+                }
+                Some(_) => Err(invalid()),
+                None => Ok(Operand::Indirect(inner.trim())),
+            };
+        }
+        return match inner.rsplit_once(')') {
+            Some((expr, index)) if is_index(index.trim(), "y") => {
+                Ok(Operand::IndirectIndexed(expr.trim()))
+            }
+            _ => Err(invalid()),
+        };
+    }
+
+    let (absolute, operand) = match operand.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("a:") => (true, operand[2..].trim()),
+        _ => (false, operand),
+    };
+    let (expr, index) = match operand.rsplit_once(',') {
+        None => {
+            return Ok(Operand::Direct {
+                expr: operand,
+                index: None,
+                absolute,
+            })
+        }
+        Some((expr, index)) => (expr.trim(), index.trim()),
+    };
+    match index.to_ascii_uppercase().as_str() {
+        "X" => Ok(Operand::Direct {
+            expr,
+            index: Some(Index::X),
+            absolute,
+        }),
+        "Y" => Ok(Operand::Direct {
+            expr,
+            index: Some(Index::Y),
+            absolute,
+        }),
+        _ if !absolute => Ok(Operand::ZeroPageRelative(expr, index)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Evaluates an expression, returning `None` if it uses a label that has not
+/// been defined yet.
+fn eval(expr: &str, labels: &BTreeMap<String, u16>, pc: u16) -> Result<Option<i64>, ErrorKind> {
+    let invalid = || ErrorKind::InvalidOperand(expr.to_string());
+
+    let expr = expr.trim();
+    let (rest, part) = if let Some(rest) = expr.strip_prefix('<') {
+        (rest, Some(false))
+    } else if let Some(rest) = expr.strip_prefix('>') {
+        (rest, Some(true))
+    } else {
+        (expr, None)
+    };
+
+    let mut total = Some(0i64);
+    let mut sign = 1;
+    let mut rest = rest.trim();
+    if let Some(negated) = rest.strip_prefix('-') {
+        sign = -1;
+        rest = negated;
+    }
+    loop {
+        let end = rest.find(['+', '-']).unwrap_or(rest.len());
+        let term = rest[..end].trim();
+        let value = if term == "*" {
+            Some(pc as i64)
+        } else if let Some(hex) = term.strip_prefix('$') {
+            Some(i64::from_str_radix(hex, 16).map_err(|_| invalid())?)
+        } else if let Some(bin) = term.strip_prefix('%') {
+            Some(i64::from_str_radix(bin, 2).map_err(|_| invalid())?)
+        } else if term.starts_with(|c: char| c.is_ascii_digit()) {
+            Some(term.parse().map_err(|_| invalid())?)
+        } else if is_identifier(term) {
+            labels.get(term).map(|&addr| addr as i64)
+        } else {
+            return Err(invalid());
+        };
+        total = total.zip(value).map(|(total, value)| total + sign * value);
+
+        if end == rest.len() {
+            break;
+        }
+        sign = if rest[end..].starts_with('+') { 1 } else { -1 };
+        rest = &rest[end + 1..];
+    }
+
+    Ok(total.map(|total| match part {
+        Some(false) => total & 0xFF,
+        Some(true) => (total >> 8) & 0xFF,
+        None => total,
+    }))
+}
+
+fn resolve(expr: &str, labels: &BTreeMap<String, u16>, pc: u16) -> Result<i64, ErrorKind> {
+    eval(expr, labels, pc)?.ok_or_else(|| {
+        let label = expr
+            .split(['<', '>', '+', '-'])
+            .map(str::trim)
+            .find(|term| is_identifier(term) && !labels.contains_key(*term))
+            .unwrap_or(expr);
+        ErrorKind::UndefinedLabel(label.to_string())
+    })
+}
+
+fn byte(value: i64) -> Result<u8, ErrorKind> {
+    match value {
+        -128..=255 => Ok(value as u8),
+        _ => Err(ErrorKind::ValueOutOfRange(value)),
+    }
+}
+
+fn word(value: i64) -> Result<u16, ErrorKind> {
+    match value {
+        0..=0xFFFF => Ok(value as u16),
+        _ => Err(ErrorKind::ValueOutOfRange(value)),
+    }
+}
+
+/// Finds the opcode for a mnemonic and addressing mode, preferring the
+/// official one where an unofficial duplicate exists.
+fn find(
+    table: &'static [Instruction; 256],
+    mnemonic: &str,
+    mode: AddressingMode,
+) -> Option<&'static Instruction> {
+    let mut matches = table
+        .iter()
+        .filter(|ins| ins.mnemonic == mnemonic && ins.mode == mode);
+    let first = matches.next()?;
+    Some(
+        std::iter::once(first)
+            .chain(matches)
+            .find(|ins| ins.official)
+            .unwrap_or(first),
+    )
+}
+
+fn select(
+    table: &'static [Instruction; 256],
+    mnemonic: &str,
+    operand: &Operand,
+    labels: &BTreeMap<String, u16>,
+    pc: u16,
+) -> Result<&'static Instruction, ErrorKind> {
+    if !table.iter().any(|ins| ins.mnemonic == mnemonic) {
+        return Err(ErrorKind::UnknownMnemonic(mnemonic.to_string()));
+    }
+
+    // the zero page form comes first where both exist
+    let (modes, expr, absolute): (&[AddressingMode], _, _) = match operand {
+        Operand::None => (&[AddressingMode::None], None, false),
+        Operand::Immediate(_) => (&[AddressingMode::Immediate], None, false),
+        Operand::Direct {
+            expr,
+            index,
+            absolute,
+        } => {
+            let modes: &[_] = match index {
+                None => &[
+                    AddressingMode::Relative,
+                    AddressingMode::ZeroPage,
+                    AddressingMode::Absolute,
+                ],
+                Some(Index::X) => &[AddressingMode::ZeroPageX, AddressingMode::AbsoluteX],
+                Some(Index::Y) => &[AddressingMode::ZeroPageY, AddressingMode::AbsoluteY],
+            };
+            (modes, Some(*expr), *absolute)
+        }
+        Operand::IndexedIndirect(expr) => (
+            &[
+                AddressingMode::IndirectX,
+                AddressingMode::AbsoluteIndexedIndirect,
+            ],
+            Some(*expr),
+            false,
+        ),
+        Operand::IndirectIndexed(_) => (&[AddressingMode::IndirectY], None, false),
+        Operand::Indirect(expr) => (
+            &[AddressingMode::ZeroPageIndirect, AddressingMode::Indirect],
+            Some(*expr),
+            false,
+        ),
+        Operand::ZeroPageRelative(..) => (&[AddressingMode::ZeroPageRelative], None, false),
+    };
+
+    let found: Vec<_> = modes
+        .iter()
+        .filter_map(|&mode| find(table, mnemonic, mode))
+        .filter(|ins| !absolute || !is_zero_page(ins.mode))
+        .collect();
+
+    let ins = match found[..] {
+        [] => return Err(ErrorKind::InvalidAddressingMode(mnemonic.to_string())),
+        [ins] => ins,
+        [zero_page, absolute, ..] => {
+            let expr = expr.unwrap_or_default();
+            let small =
+                expr.starts_with(['<', '>']) || matches!(eval(expr, labels, pc)?, Some(0..=0xFF));
+            if small {
+                zero_page
+            } else {
+                absolute
+            }
+        }
+    };
+    Ok(ins)
+}
+
+fn is_zero_page(mode: AddressingMode) -> bool {
+    matches!(
+        mode,
+        AddressingMode::ZeroPage
+            | AddressingMode::ZeroPageX
+            | AddressingMode::ZeroPageY
+            | AddressingMode::IndirectX
+            | AddressingMode::IndirectY
+            | AddressingMode::ZeroPageIndirect
+            | AddressingMode::ZeroPageRelative
+    )
+}
+
+fn encode(
+    ins: &Instruction,
+    operand: &Operand,
+    labels: &BTreeMap<String, u16>,
+    pc: u16,
+    out: &mut Vec<u8>,
+) -> Result<(), ErrorKind> {
+    let branch = |target: &str, next: u16| -> Result<u8, ErrorKind> {
+        let offset = resolve(target, labels, pc)? - next as i64;
+        match offset {
+            -128..=127 => Ok(offset as u8),
+            _ => Err(ErrorKind::BranchOutOfRange(offset)),
+        }
+    };
+
+    let expr = match operand {
+        Operand::None => return Ok(()),
+        Operand::ZeroPageRelative(zp, target) => {
+            let zp = resolve(zp, labels, pc)?;
+            out.push(byte_unsigned(zp)?);
+            out.push(branch(target, pc.wrapping_add(3))?);
+            return Ok(());
+        }
+        Operand::Immediate(expr) => {
+            out.push(byte(resolve(expr, labels, pc)?)?);
+            return Ok(());
+        }
+        Operand::Direct { expr, .. }
+        | Operand::IndexedIndirect(expr)
+        | Operand::IndirectIndexed(expr)
+        | Operand::Indirect(expr) => expr,
+    };
+
+    if ins.mode == AddressingMode::Relative {
+        out.push(branch(expr, pc.wrapping_add(2))?);
+    } else if is_zero_page(ins.mode) {
+        out.push(byte_unsigned(resolve(expr, labels, pc)?)?);
+    } else {
+        out.extend_from_slice(&word(resolve(expr, labels, pc)?)?.to_le_bytes());
+    }
+    Ok(())
+}
+
+fn byte_unsigned(value: i64) -> Result<u8, ErrorKind> {
+    match value {
+        0..=0xFF => Ok(value as u8),
+        _ => Err(ErrorKind::ValueOutOfRange(value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(source: &str) -> Vec<u8> {
+        assemble(source, 0x8000).unwrap().bytes
+    }
+
+    fn error(source: &str) -> Error {
+        assemble(source, 0x8000).unwrap_err()
+    }
+
+    #[test]
+    fn addressing_modes() {
+        let source = "
+            nop
+            asl a
+            lda #$12
+            lda $12
+            lda $12,x
+            ldx $12,y
+            lda $1234
+            lda $1234,x
+            lda $1234,y
+            jmp ($1234)
+            lda ($12,x)
+            lda ($12),y
+        loop:
+            bne loop
+        ";
+        #[rustfmt::skip]
+        let expected = [
+            0xEA,
+            0x0A,
+            0xA9, 0x12,
+            0xA5, 0x12,
+            0xB5, 0x12,
+            0xB6, 0x12,
+            0xAD, 0x34, 0x12,
+            0xBD, 0x34, 0x12,
+            0xB9, 0x34, 0x12,
+            0x6C, 0x34, 0x12,
+            0xA1, 0x12,
+            0xB1, 0x12,
+            0xD0, 0xFE,
+        ];
+        assert_eq!(bytes(source), expected);
+    }
+
+    #[test]
+    fn cmos_addressing_modes() {
+        let source = "
+            lda ($12)
+            jmp ($1234,x)
+        loop:
+            bbr0 $12,loop
+        ";
+        let program = assemble_for(Variant::Wdc65C02, source, 0x8000).unwrap();
+        assert_eq!(
+            program.bytes,
+            [0xB2, 0x12, 0x7C, 0x34, 0x12, 0x0F, 0x12, 0xFD]
+        );
+
+        assert_eq!(
+            error("lda ($12)").kind,
+            ErrorKind::InvalidAddressingMode("LDA".to_string())
+        );
+    }
+
+    #[test]
+    fn zero_page_is_only_used_for_operands_known_to_fit() {
+        let source = "
+        back = $20
+            lda back
+            lda fwd
+            lda <fwd
+            lda a:back
+            lda $0020
+        fwd = $10
+        ";
+        #[rustfmt::skip]
+        let expected = [
+            0xA5, 0x20,
+            // the value of a forward reference is not known in the first pass
+            0xAD, 0x10, 0x00,
+            0xA5, 0x10,
+            0xAD, 0x20, 0x00,
+            0xA5, 0x20,
+        ];
+        assert_eq!(bytes(source), expected);
+    }
+
+    #[test]
+    fn forward_labels_resolve_to_their_final_address() {
+        let program = assemble("jmp end\nnop\nend: rts", 0x8000).unwrap();
+        assert_eq!(program.bytes, [0x4C, 0x04, 0x80, 0xEA, 0x60]);
+        assert_eq!(program.labels["end"], 0x8004);
+    }
+
+    #[test]
+    fn org_fills_gaps_with_zeros() {
+        let program = assemble(".org $C000\nnop\n.org $C003\n.word *", 0).unwrap();
+        assert_eq!(program.origin, 0xC000);
+        assert_eq!(program.bytes, [0xEA, 0x00, 0x00, 0x03, 0xC0]);
+    }
+
+    #[test]
+    fn org_cannot_move_backwards() {
+        assert_eq!(
+            error("nop\n.org $7000"),
+            Error {
+                line: 2,
+                kind: ErrorKind::OrgBackwards(0x7000)
+            }
+        );
+    }
+
+    #[test]
+    fn unofficial_mnemonics_and_their_aliases() {
+        let source = "
+            isc $12
+            isb $12
+            sbx #$01
+            asr #$01
+            lax $12
+            kil
+        ";
+        #[rustfmt::skip]
+        let expected = [
+            0xE7, 0x12,
+            0xE7, 0x12,
+            0xCB, 0x01,
+            0x4B, 0x01,
+            0xA7, 0x12,
+            0x02,
+        ];
+        assert_eq!(bytes(source), expected);
+    }
+
+    #[test]
+    fn branches_must_stay_in_range() {
+        assert_eq!(
+            error("bne far\n.org $8082\nfar: nop").kind,
+            ErrorKind::BranchOutOfRange(128)
+        );
+        assert_eq!(bytes("bne far\n.org $8081\nfar:")[..2], [0xD0, 0x7F][..]);
+
+        let source = ".org $7F81\nback: nop\n.org $8000\nbeq back";
+        assert_eq!(
+            assemble(source, 0).unwrap_err(),
+            Error {
+                line: 4,
+                kind: ErrorKind::BranchOutOfRange(-129)
+            }
+        );
+    }
+
+    #[test]
+    fn low_and_high_byte_selection() {
+        let source = "
+        vector = $ABCD
+            lda #<vector
+            ldx #>vector
+            .byte <vector, >vector, >vector+1
+            .word vector
+        ";
+        assert_eq!(
+            bytes(source),
+            [0xA9, 0xCD, 0xA2, 0xAB, 0xCD, 0xAB, 0xAB, 0xCD, 0xAB]
+        );
+    }
+
+    #[test]
+    fn errors_report_their_line() {
+        assert_eq!(
+            error("nop\nfoo"),
+            Error {
+                line: 2,
+                kind: ErrorKind::UnknownMnemonic("FOO".to_string())
+            }
+        );
+        assert_eq!(
+            error("stx $1234,x").kind,
+            ErrorKind::InvalidAddressingMode("STX".to_string())
+        );
+        assert_eq!(
+            error("jmp nowhere").kind,
+            ErrorKind::UndefinedLabel("nowhere".to_string())
+        );
+        assert_eq!(
+            error("a: nop\na: nop").kind,
+            ErrorKind::DuplicateLabel("a".to_string())
+        );
+        assert_eq!(error("lda #$100").kind, ErrorKind::ValueOutOfRange(0x100));
+    }
+}
@@ -1,17 +1,39 @@
-use crate::{mem::Mem, rom::Rom};
+use std::cell::RefCell;
+
+use crate::{
+    mapper::{self, Mapper},
+    mem::Mem,
+    rom::{self, Rom},
+    Error,
+};
 
 #[derive(Debug)]
 pub struct Bus {
     ram: [u8; 2048],
-    rom: Rom,
+    mapper: Box<dyn Mapper>,
+    faults: RefCell<Vec<Error>>,
 }
 
 impl Bus {
-    pub fn new(rom: Rom) -> Self {
-        Self {
+    /// Fails when the cartridge uses a mapper that is not emulated.
+    pub fn new(rom: Rom) -> Result<Self, rom::Error> {
+        Ok(Self {
             ram: [0; 2048],
-            rom,
-        }
+            mapper: mapper::from_rom(rom)?,
+            faults: RefCell::new(Vec::new()),
+        })
+    }
+
+    pub fn mapper(&self) -> &dyn Mapper {
+        self.mapper.as_ref()
+    }
+
+    pub fn mapper_mut(&mut self) -> &mut dyn Mapper {
+        self.mapper.as_mut()
+    }
+
+    fn fault(&self, error: Error) {
+        self.faults.borrow_mut().push(error);
     }
 }
 
@@ -19,25 +41,22 @@ const RAM: u16 = 0x0000;
 const RAM_MIRRORS_END: u16 = 0x1FFF;
 const PPU_REGISTERS: u16 = 0x2000;
 const PPU_REGISTERS_MIRRORS_END: u16 = 0x3FFF;
+const CARTRIDGE: u16 = 0x4020;
 
 impl Mem for Bus {
     fn read_byte(&self, addr: u16) -> u8 {
         match addr {
             RAM..=RAM_MIRRORS_END => self.ram[(addr & 0x7FF) as usize],
             PPU_REGISTERS..=PPU_REGISTERS_MIRRORS_END => {
-                error!("PPU registers not implemented");
+                self.fault(Error::UnimplementedHardware { addr });
                 0
             }
-            0x8000..=0xFFFF => {
-                let addr = (addr - 0x8000) as usize;
-                if self.rom.prg_rom.len() == 0x4000 && addr >= 0x4000 {
-                    self.rom.prg_rom[addr % 0x4000]
-                } else {
-                    self.rom.prg_rom[addr]
-                }
-            }
+            CARTRIDGE..=0xFFFF => self.mapper.cpu_read(addr).unwrap_or_else(|error| {
+                self.fault(error);
+                0
+            }),
             _ => {
-                debug!("Unmapped read at address {:#06x}", addr);
+                self.fault(Error::UnmappedRead { addr });
                 0
             }
         }
@@ -46,9 +65,73 @@ impl Mem for Bus {
     fn write_byte(&mut self, addr: u16, value: u8) {
         match addr {
             RAM..=RAM_MIRRORS_END => self.ram[(addr & 0x7FF) as usize] = value,
-            PPU_REGISTERS..=PPU_REGISTERS_MIRRORS_END => error!("PPU not supported yet"),
-            0x8000..=0xFFFF => panic!("Attempted to write to cartridge rom address {:#06x}", addr),
-            _ => debug!("Unmapped write at address {:#06x}", addr),
+            PPU_REGISTERS..=PPU_REGISTERS_MIRRORS_END => {
+                self.fault(Error::UnimplementedHardware { addr })
+            }
+            CARTRIDGE..=0xFFFF => {
+                if let Err(error) = self.mapper.cpu_write(addr, value) {
+                    self.fault(error);
+                }
+            }
+            _ => self.fault(Error::UnmappedWrite { addr, value }),
+        }
+    }
+
+    /// Like `read_byte`, but unmapped and unimplemented addresses read as 0
+    /// without recording a fault.
+    fn peek_byte(&self, addr: u16) -> u8 {
+        match addr {
+            RAM..=RAM_MIRRORS_END => self.ram[(addr & 0x7FF) as usize],
+            CARTRIDGE..=0xFFFF => self.mapper.cpu_read(addr).unwrap_or(0),
+            _ => 0,
         }
     }
+
+    fn irq(&self) -> bool {
+        self.mapper.irq()
+    }
+
+    fn clock(&mut self, cycles: usize) {
+        self.mapper.clock(cycles);
+    }
+
+    fn take_faults(&mut self) -> Vec<Error> {
+        std::mem::take(self.faults.get_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rom::Mirroring;
+
+    fn bus() -> Bus {
+        Bus::new(Rom {
+            prg_rom: vec![0xEA; 0x8000],
+            chr_rom: vec![0; 0x2000],
+            mapper: 0,
+            submapper: 0,
+            mirroring: Mirroring::Horizontal,
+            prg_ram_size: 0,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn peeking_records_no_faults() {
+        let mut bus = bus();
+        bus.write_byte(0x0801, 0x42);
+
+        assert_eq!(bus.peek_byte(0x0001), 0x42);
+        assert_eq!(bus.peek_byte(0x8000), 0xEA);
+        assert_eq!(bus.peek_byte(0x2002), 0x00);
+        assert_eq!(bus.peek_byte(0x4000), 0x00);
+        assert!(bus.take_faults().is_empty());
+
+        bus.read_byte(0x2002);
+        assert_eq!(
+            bus.take_faults(),
+            [Error::UnimplementedHardware { addr: 0x2002 }]
+        );
+    }
 }
@@ -1,7 +1,30 @@
-use crate::{bus::Bus, instruction::INSTRUCTIONS, mem::Mem};
+use std::{cell::Cell, collections::HashSet};
+
+use crate::{
+    bus::Bus,
+    error::{FaultPolicy, Policy},
+    history::{Entry, History},
+    instruction::{Access, Instruction, CMOS_INSTRUCTIONS, INSTRUCTIONS},
+    mem::Mem,
+    Error,
+};
+
+use self::tick::TickState;
+
+mod tick;
 
 pub const STACK: u16 = 0x0100;
 
+/// CPU cycles in an NTSC frame, 262 scanlines of 341 PPU dots at three dots
+/// per CPU cycle, rounded up.
+pub const CYCLES_PER_FRAME: usize = 29781;
+
+pub const XAA_MAGIC: u8 = 0xEE;
+
+pub const NMI_VECTOR: u16 = 0xFFFA;
+pub const RESET_VECTOR: u16 = 0xFFFC;
+pub const IRQ_VECTOR: u16 = 0xFFFE;
+
 #[derive(Debug, Default, Clone)]
 pub struct Status {
     pub carry: bool,
@@ -59,7 +82,11 @@ impl From<Status> for u8 {
     }
 }
 
-#[derive(Debug)]
+fn page_crossed(a: u16, b: u16) -> bool {
+    a & 0xFF00 != b & 0xFF00
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AddressingMode {
     Immediate,
     ZeroPage,
@@ -72,23 +99,46 @@ pub enum AddressingMode {
     IndirectX,
     IndirectY,
     Relative,
+    /// `(zp)`, 65C02 only.
+    ZeroPageIndirect,
+    /// `(abs,X)`, used by the 65C02 `JMP`.
+    AbsoluteIndexedIndirect,
+    /// `zp,rel`, used by the 65C02 `BBR` and `BBS` instructions.
+    ZeroPageRelative,
     None,
 }
 
 #[derive(Debug)]
-pub struct Cpu {
+pub struct Cpu<M: Mem = Bus> {
     pub a: u8,
     pub x: u8,
     pub y: u8,
     pub sp: u8,
     pub status: Status,
     pub pc: u16,
-    pub running: bool,
     pub cycles: usize,
-    pub bus: Bus,
+    /// The chip specific constant the unstable XAA and LXA opcodes OR into A.
+    pub xaa_magic: u8,
+    pub variant: Variant,
+    /// Set when the CPU executed a JAM opcode. Only a reset recovers from it.
+    pub jammed: Option<Jam>,
+    /// Set by the 65C02 WAI instruction until an interrupt is signalled.
+    pub waiting: bool,
+    /// Addresses at which `run` and friends stop before executing the instruction.
+    pub breakpoints: HashSet<u16>,
+    /// How faults raised while stepping are handled.
+    pub fault_policy: FaultPolicy,
+    /// The last instructions executed, for diagnosing crashes.
+    pub history: History,
+    pub bus: M,
+    fault: Cell<Option<Error>>,
+    nmi_line: bool,
+    nmi_pending: bool,
+    irq_line: bool,
+    ticks: TickState,
 }
 
-impl std::fmt::Display for Cpu {
+impl<M: Mem> std::fmt::Display for Cpu<M> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
@@ -103,7 +153,7 @@ impl std::fmt::Display for Cpu {
     }
 }
 
-impl Mem for Cpu {
+impl<M: Mem> Mem for Cpu<M> {
     #[inline(always)]
     fn read_byte(&self, addr: u16) -> u8 {
         self.bus.read_byte(addr)
@@ -133,6 +183,77 @@ pub enum Register {
     S,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupt {
+    Nmi,
+    Irq,
+    Brk,
+}
+
+/// The flavour of 6502 being emulated.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    /// The NES CPU, an NMOS 6502 with decimal mode disconnected.
+    #[default]
+    Ricoh2A03,
+    /// A stock NMOS 6502 with working decimal mode.
+    Nmos6502,
+    /// The WDC 65C02 with its additional instructions and addressing modes.
+    Wdc65C02,
+}
+
+impl Variant {
+    pub fn has_decimal_mode(&self) -> bool {
+        match self {
+            Variant::Ricoh2A03 => false,
+            Variant::Nmos6502 | Variant::Wdc65C02 => true,
+        }
+    }
+
+    pub fn is_cmos(&self) -> bool {
+        *self == Variant::Wdc65C02
+    }
+
+    /// The opcode table of this variant.
+    pub fn instructions(&self) -> &'static [Instruction; 256] {
+        match self {
+            Variant::Ricoh2A03 | Variant::Nmos6502 => &INSTRUCTIONS,
+            Variant::Wdc65C02 => &CMOS_INSTRUCTIONS,
+        }
+    }
+}
+
+/// The state of a CPU locked up by one of the JAM (KIL) opcodes, or stopped
+/// by the 65C02 STP instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jam {
+    pub opcode: u8,
+    pub addr: u16,
+}
+
+/// Why `Cpu::step` or one of the `Cpu::run` methods handed control back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The PC reached an address in `Cpu::breakpoints`. The instruction
+    /// there has not been executed yet.
+    Breakpoint(u16),
+    /// A BRK at the given address was executed and its interrupt taken, so
+    /// resuming continues in the IRQ/BRK handler.
+    Brk(u16),
+    /// The CPU is locked up by a JAM opcode until the next reset.
+    Jammed(Jam),
+    /// The cycle budget given to a budgeted run has been used up. The last
+    /// instruction ran `overshoot` cycles past the end of the budget.
+    CycleBudgetExhausted { overshoot: usize },
+    /// A whole video frame worth of cycles has been executed, `overshoot`
+    /// cycles of which belong to the next frame.
+    FrameComplete { overshoot: usize },
+    /// The predicate passed to `Cpu::run_until` returned true.
+    ConditionMet,
+    /// An instruction at the given address jumped or branched to itself.
+    Trapped(u16),
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum BranchCondition {
     CarrySet,
@@ -143,10 +264,28 @@ pub enum BranchCondition {
     MinusClear,
     OverflowSet,
     OverflowClear,
+    Always,
 }
 
-impl Cpu {
-    pub fn new(bus: Bus) -> Self {
+impl BranchCondition {
+    /// The condition tested by one of the eight NMOS branch opcodes.
+    fn from_opcode(opcode: u8) -> Self {
+        match opcode {
+            0x10 => BranchCondition::MinusClear,
+            0x30 => BranchCondition::MinusSet,
+            0x50 => BranchCondition::OverflowClear,
+            0x70 => BranchCondition::OverflowSet,
+            0x90 => BranchCondition::CarryClear,
+            0xB0 => BranchCondition::CarrySet,
+            0xD0 => BranchCondition::ZeroClear,
+            0xF0 => BranchCondition::ZeroSet,
+            _ => BranchCondition::Always,
+        }
+    }
+}
+
+impl<M: Mem> Cpu<M> {
+    pub fn new(bus: M) -> Self {
         let mut cpu = Self {
             a: 0,
             x: 0,
@@ -154,9 +293,20 @@ impl Cpu {
             sp: 0,
             status: 0.into(),
             pc: 0,
-            running: true,
             cycles: 0,
+            xaa_magic: XAA_MAGIC,
+            variant: Variant::default(),
+            jammed: None,
+            waiting: false,
+            breakpoints: HashSet::new(),
+            fault_policy: FaultPolicy::default(),
+            history: History::default(),
             bus,
+            fault: Cell::new(None),
+            nmi_line: false,
+            nmi_pending: false,
+            irq_line: false,
+            ticks: TickState::default(),
         };
         cpu.reset();
         cpu
@@ -168,109 +318,318 @@ impl Cpu {
         self.y = 0;
         self.sp = 0xFD;
         self.status = 0b00100100.into();
-        self.pc = self.read_word(0xFFFC);
-        self.cycles = 0;
+        self.pc = self.read_word(RESET_VECTOR);
+        // the reset sequence takes 7 cycles before the first instruction
+        self.cycles = 7;
+        self.nmi_pending = false;
+        self.jammed = None;
+        self.waiting = false;
+        self.ticks = TickState::default();
     }
 
-    fn update_zero_and_negative(&mut self, value: u8) {
-        self.status.zero = value == 0;
-        self.status.negative = value >> 7 == 1;
+    /// Drives the NMI input. NMI is edge-triggered, so an interrupt is only
+    /// latched when the line goes from deasserted to asserted.
+    pub fn set_nmi(&mut self, asserted: bool) {
+        if asserted && !self.nmi_line {
+            self.nmi_pending = true;
+        }
+        self.nmi_line = asserted;
+    }
+
+    /// Drives the IRQ input. IRQ is level-triggered and is serviced before
+    /// every instruction for as long as the line stays asserted and
+    /// interrupts are not disabled.
+    pub fn set_irq(&mut self, asserted: bool) {
+        self.irq_line = asserted;
+    }
+
+    /// IRQ is wired-OR, either `set_irq` or a device on the bus can assert it.
+    fn irq_asserted(&self) -> bool {
+        self.irq_line || self.bus.irq()
+    }
+
+    /// The interrupt that would be serviced next, without acknowledging it.
+    fn pending_interrupt(&self) -> Option<Interrupt> {
+        if self.nmi_pending {
+            Some(Interrupt::Nmi)
+        } else if self.irq_asserted() && !self.status.disable_interrupts {
+            Some(Interrupt::Irq)
+        } else {
+            None
+        }
+    }
+
+    fn poll_interrupts(&mut self) -> Option<Interrupt> {
+        let interrupt = self.pending_interrupt();
+        if interrupt == Some(Interrupt::Nmi) {
+            self.nmi_pending = false;
+        }
+        interrupt
+    }
+
+    fn interrupt(&mut self, interrupt: Interrupt) {
+        let vector = match interrupt {
+            Interrupt::Nmi => NMI_VECTOR,
+            Interrupt::Irq | Interrupt::Brk => IRQ_VECTOR,
+        };
+
+        self.push_word(self.pc);
+
+        let mut status = self.status.clone();
+        status.b1 = interrupt == Interrupt::Brk;
+        status.b2 = true;
+        self.push_byte(status.into());
+
+        self.status.disable_interrupts = true;
+        if self.variant.is_cmos() {
+            self.status.decimal = false;
+        }
+        self.pc = self.read_word(vector);
     }
 
-    fn update_negative(&mut self, value: u8) {
+    fn update_zero_and_negative(&mut self, value: u8) {
+        self.status.zero = value == 0;
         self.status.negative = value >> 7 == 1;
     }
 
-    fn load(&mut self, register: Register, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr);
+    fn load(&mut self, register: Register, value: u8) {
         self.set_register(register, value);
         self.update_zero_and_negative(value);
     }
 
-    pub fn run(&mut self) {
-        self.run_with_callback(|_| {});
+    pub fn run(&mut self) -> Result<StopReason, Error> {
+        self.run_with_callback(|_| {})
+    }
+
+    /// Runs until `step` reports a reason to stop or a breakpoint is hit.
+    /// A breakpoint at the current PC is ignored so that execution can be
+    /// resumed from it.
+    pub fn run_with_callback<F>(&mut self, callback: F) -> Result<StopReason, Error>
+    where
+        F: FnMut(&mut Cpu<M>),
+    {
+        self.run_until_with_callback(|_| false, callback)
+    }
+
+    pub fn run_until<P>(&mut self, predicate: P) -> Result<StopReason, Error>
+    where
+        P: FnMut(&Cpu<M>) -> bool,
+    {
+        self.run_until_with_callback(predicate, |_| {})
     }
 
-    pub fn run_with_callback<F>(&mut self, mut callback: F)
+    /// Like `run_with_callback`, but additionally stops with
+    /// `StopReason::ConditionMet` as soon as `predicate` holds after an
+    /// instruction.
+    pub fn run_until_with_callback<P, F>(
+        &mut self,
+        mut predicate: P,
+        mut callback: F,
+    ) -> Result<StopReason, Error>
     where
-        F: FnMut(&mut Cpu),
+        P: FnMut(&Cpu<M>) -> bool,
+        F: FnMut(&mut Cpu<M>),
     {
-        while self.running {
+        let mut first = true;
+        loop {
+            if !first && self.breakpoints.contains(&self.pc) {
+                return Ok(StopReason::Breakpoint(self.pc));
+            }
+            first = false;
+
             callback(self);
-            self.step();
+            if let Some(reason) = self.step()? {
+                return Ok(reason);
+            }
+            if predicate(self) {
+                return Ok(StopReason::ConditionMet);
+            }
         }
     }
 
-    pub fn step(&mut self) {
+    /// Runs until the PC reaches `addr`.
+    pub fn run_until_pc(&mut self, addr: u16) -> Result<StopReason, Error> {
+        self.run_until(|cpu| cpu.pc == addr)
+    }
+
+    /// Runs until an instruction jumps or branches to itself, the way test
+    /// programs signal that they passed or which test failed.
+    pub fn run_until_trap(&mut self) -> Result<StopReason, Error> {
+        let mut pc = self.pc;
+        let reason = self.run_until(|cpu| {
+            let trapped = cpu.pc == pc && !cpu.waiting;
+            pc = cpu.pc;
+            trapped
+        })?;
+        match reason {
+            StopReason::ConditionMet => Ok(StopReason::Trapped(self.pc)),
+            reason => Ok(reason),
+        }
+    }
+
+    pub fn run_for_cycles(&mut self, cycles: usize) -> Result<StopReason, Error> {
+        self.run_for_cycles_with_callback(cycles, |_| {})
+    }
+
+    /// Runs until at least `cycles` CPU cycles have elapsed. Instructions are
+    /// never split, so the budget is usually overshot by a few cycles which
+    /// callers can deduct from the next budget.
+    pub fn run_for_cycles_with_callback<F>(
+        &mut self,
+        cycles: usize,
+        callback: F,
+    ) -> Result<StopReason, Error>
+    where
+        F: FnMut(&mut Cpu<M>),
+    {
+        if cycles == 0 {
+            return Ok(StopReason::CycleBudgetExhausted { overshoot: 0 });
+        }
+
+        let target = self.cycles + cycles;
+        match self.run_until_with_callback(|cpu| cpu.cycles >= target, callback)? {
+            StopReason::ConditionMet => Ok(StopReason::CycleBudgetExhausted {
+                overshoot: self.cycles - target,
+            }),
+            reason => Ok(reason),
+        }
+    }
+
+    /// Runs up to the next multiple of `CYCLES_PER_FRAME`.
+    pub fn run_frame(&mut self) -> Result<StopReason, Error> {
+        let target = (self.cycles / CYCLES_PER_FRAME + 1) * CYCLES_PER_FRAME;
+        match self.run_until(|cpu| cpu.cycles >= target)? {
+            StopReason::ConditionMet => Ok(StopReason::FrameComplete {
+                overshoot: self.cycles - target,
+            }),
+            reason => Ok(reason),
+        }
+    }
+
+    /// Executes a single instruction, or services a pending interrupt.
+    /// Returns `Some` when the instruction is something a frontend may want
+    /// to stop at, such as BRK or a JAM, and `Err` for faults whose policy
+    /// is `Policy::Halt`.
+    pub fn step(&mut self) -> Result<Option<StopReason>, Error> {
+        // finish an instruction `tick` is partway through, that counts as
+        // the step
+        if self.ticks.in_progress() {
+            while self.ticks.in_progress() {
+                if let Some(stop) = self.tick()? {
+                    return Ok(Some(stop));
+                }
+            }
+            return Ok(None);
+        }
+        // the interrupts are polled afresh below
+        self.ticks = TickState::default();
+
+        if let Some(jam) = self.jammed {
+            return Ok(Some(StopReason::Jammed(jam)));
+        }
+
+        if self.waiting {
+            if self.nmi_pending || self.irq_asserted() {
+                self.waiting = false;
+            } else {
+                self.cycles += 1;
+                self.bus.clock(1);
+                return Ok(None);
+            }
+        }
+
+        if let Some(interrupt) = self.poll_interrupts() {
+            trace!("{}, {:?}", self, interrupt);
+            self.interrupt(interrupt);
+            self.cycles += 7;
+            self.bus.clock(7);
+            self.check_faults()?;
+            return Ok(None);
+        }
+
         let opcode = self.read_byte(self.pc);
+        self.record_history(opcode);
 
-        let ins = &INSTRUCTIONS[opcode as usize];
+        let ins = &self.variant.instructions()[opcode as usize];
 
-        let pc = self.pc + 1;
-        self.pc += 1;
+        let start = self.cycles;
+        self.pc = self.pc.wrapping_add(1);
         self.cycles += ins.cycles;
 
         trace!("{}, {:?}", self, ins);
 
-        match opcode {
-            0x00 => {
-                self.status.b1 = true;
-                self.running = false;
-                return;
-            }
-
-            0xEA => (),
-            0x1A | 0x3A | 0x5A | 0x7A | 0xDA | 0xFA | 0x80 | 0x82 | 0x89 | 0xC2 | 0xE2 | 0x04
-            | 0x44 | 0x64 | 0x14 | 0x34 | 0x54 | 0x74 | 0xD4 | 0xF4 | 0x0C | 0x1C | 0x3C | 0x5C
-            | 0x7C | 0xDC | 0xFC => (),
+        let stop = if self.variant.is_cmos() {
+            self.execute_cmos(opcode, ins)
+        } else {
+            self.execute(opcode, ins)
+        };
 
-            0x69 | 0x65 | 0x75 | 0x6D | 0x7D | 0x79 | 0x61 | 0x71 => self.adc(&ins.mode),
-            0xE9 | 0xE5 | 0xF5 | 0xED | 0xFD | 0xF9 | 0xE1 | 0xF1 | 0xEB => self.sbc(&ins.mode),
+        // jumps and branches set the pc themselves, even when not taken
+        if ins.access != Access::None && ins.mode != AddressingMode::ZeroPageRelative {
+            self.pc = self.pc.wrapping_add(ins.bytes as u16 - 1);
+        }
 
-            0x4A => self.lsr_a(),
-            0x46 | 0x56 | 0x4E | 0x5E => {
-                self.lsr(&ins.mode);
-            }
+        self.bus.clock(self.cycles - start);
+        self.check_faults()?;
+        Ok(stop)
+    }
 
-            0x4C | 0x6C => self.jmp(&ins.mode),
-            0x20 => self.jsr(&ins.mode),
-            0x40 => self.rti(),
-            0x60 => self.rts(),
+    fn record_history(&mut self, opcode: u8) {
+        self.history.record(Entry {
+            pc: self.pc,
+            opcode,
+            a: self.a,
+            x: self.x,
+            y: self.y,
+            sp: self.sp,
+            status: u8::from(&self.status),
+            cycles: self.cycles,
+        });
+    }
 
-            0xB0 => self.branch(BranchCondition::CarrySet, &ins.mode),
-            0x90 => self.branch(BranchCondition::CarryClear, &ins.mode),
-            0xF0 => self.branch(BranchCondition::ZeroSet, &ins.mode),
-            0xD0 => self.branch(BranchCondition::ZeroClear, &ins.mode),
-            0x30 => self.branch(BranchCondition::MinusSet, &ins.mode),
-            0x10 => self.branch(BranchCondition::MinusClear, &ins.mode),
-            0x70 => self.branch(BranchCondition::OverflowSet, &ins.mode),
-            0x50 => self.branch(BranchCondition::OverflowClear, &ins.mode),
+    fn execute(&mut self, opcode: u8, ins: &Instruction) -> Option<StopReason> {
+        let mut stop = None;
 
-            0x0A => self.asl_a(),
-            0x06 | 0x16 | 0x0E | 0x1E => {
-                self.asl(&ins.mode);
+        match ins.access {
+            Access::Read => {
+                let value = self.read_operand(&ins.mode);
+                self.read_op(opcode, value);
+                return None;
             }
-
-            0x6A => self.ror_a(),
-            0x66 | 0x76 | 0x6E | 0x7E => {
-                self.ror(&ins.mode);
+            Access::Write => {
+                let (addr, page_crossed) = self.get_operand_address(&ins.mode, self.pc);
+                self.write_op(opcode, addr, page_crossed);
+                return None;
             }
-
-            0x2A => self.rol_a(),
-            0x26 | 0x36 | 0x2E | 0x3E => {
-                self.rol(&ins.mode);
+            Access::ReadModifyWrite => {
+                let (addr, _) = self.get_operand_address(&ins.mode, self.pc);
+                let value = self.read_byte(addr);
+                let value = self.modify_op(opcode, value);
+                self.write_byte(addr, value);
+                return None;
             }
+            Access::None => (),
+        }
 
-            0x07 | 0x17 | 0x0F | 0x1F | 0x1B | 0x03 | 0x13 => self.slo(&ins.mode),
-
-            0x27 | 0x37 | 0x2F | 0x3F | 0x3b | 0x33 | 0x23 => self.rla(&ins.mode),
+        match opcode {
+            0x00 => stop = Some(self.brk()),
+            0x02 | 0x12 | 0x22 | 0x32 | 0x42 | 0x52 | 0x62 | 0x72 | 0x92 | 0xB2 | 0xD2 | 0xF2 => {
+                stop = Some(self.jam(opcode))
+            }
 
-            0x47 | 0x57 | 0x4F | 0x5f | 0x5b | 0x43 | 0x53 => self.sre(&ins.mode),
+            0x4A => self.a = self.lsr(self.a),
+            0x0A => self.a = self.asl(self.a),
+            0x6A => self.a = self.ror(self.a),
+            0x2A => self.a = self.rol(self.a),
 
-            0x67 | 0x77 | 0x6f | 0x7f | 0x7b | 0x63 | 0x73 => self.rra(&ins.mode),
+            0x4C | 0x6C => self.jmp(&ins.mode),
+            0x20 => self.jsr(&ins.mode),
+            0x40 => self.rti(),
+            0x60 => self.rts(),
 
-            0x24 | 0x2C => self.bit(&ins.mode),
+            0x10 | 0x30 | 0x50 | 0x70 | 0x90 | 0xB0 | 0xD0 | 0xF0 => {
+                self.branch(BranchCondition::from_opcode(opcode), &ins.mode)
+            }
 
             0x18 => self.status.carry = false,
             0x38 => self.status.carry = true,
@@ -279,22 +638,12 @@ impl Cpu {
             0xB8 => self.status.overflow = false,
             0xD8 => self.status.decimal = false,
             0xF8 => self.status.decimal = true,
-            0xA9 | 0xA5 | 0xB5 | 0xAD | 0xBD | 0xB9 | 0xA1 | 0xB1 => {
-                self.load(Register::A, &ins.mode)
-            }
 
             0x08 => self.php(),
             0x28 => self.plp(),
             0x48 => self.pha(),
             0x68 => self.pla(),
 
-            0xA2 | 0xA6 | 0xB6 | 0xAE | 0xBE => self.load(Register::X, &ins.mode),
-            0xA0 | 0xA4 | 0xB4 | 0xAC | 0xBC => self.load(Register::Y, &ins.mode),
-
-            0xA7 | 0xB7 | 0xAF | 0xBF | 0xA3 | 0xB3 => self.lax(&ins.mode),
-
-            0x87 | 0x97 | 0x8f | 0x83 => self.sax(&ins.mode),
-
             0xAA => self.transfer(Register::A, Register::X),
             0x8A => self.transfer(Register::X, Register::A),
             0xA8 => self.transfer(Register::A, Register::Y),
@@ -302,42 +651,186 @@ impl Cpu {
             0x9A => self.transfer(Register::X, Register::S),
             0xBA => self.transfer(Register::S, Register::X),
 
-            0x85 | 0x95 | 0x8D | 0x9D | 0x99 | 0x81 | 0x91 => self.store(Register::A, &ins.mode),
-            0x86 | 0x96 | 0x8E => self.store(Register::X, &ins.mode),
-            0x84 | 0x94 | 0x8C => self.store(Register::Y, &ins.mode),
-
             0xE8 => self.increment_register(Register::X),
             0xC8 => self.increment_register(Register::Y),
             0xCA => self.decrement_register(Register::X),
             0x88 => self.decrement_register(Register::Y),
 
-            0xE6 | 0xF6 | 0xEE | 0xFE => {
-                self.increment_memory(&ins.mode);
-            }
-            0xC6 | 0xD6 | 0xCE | 0xDE => {
-                self.decrement_memory(&ins.mode);
+            _ => (),
+        }
+
+        stop
+    }
+
+    /// Applies the operand of an `Access::Read` instruction. Shared by `step`
+    /// and `tick` so both agree on what each opcode does.
+    fn read_op(&mut self, opcode: u8, value: u8) {
+        match opcode {
+            0xA9 | 0xA5 | 0xB5 | 0xAD | 0xBD | 0xB9 | 0xA1 | 0xB1 => self.load(Register::A, value),
+            0xA2 | 0xA6 | 0xB6 | 0xAE | 0xBE => self.load(Register::X, value),
+            0xA0 | 0xA4 | 0xB4 | 0xAC | 0xBC => self.load(Register::Y, value),
+
+            0xA7 | 0xB7 | 0xAF | 0xBF | 0xA3 | 0xB3 => self.lax(value),
+            0xAB => self.lxa(value),
+            0xBB => self.las(value),
+
+            0x69 | 0x65 | 0x75 | 0x6D | 0x7D | 0x79 | 0x61 | 0x71 => self.add_to_a(value),
+            0xE9 | 0xE5 | 0xF5 | 0xED | 0xFD | 0xF9 | 0xE1 | 0xF1 | 0xEB => {
+                self.subtract_from_a(value)
             }
 
-            0xE7 | 0xF7 | 0xEF | 0xFF | 0xFB | 0xE3 | 0xF3 => self.isb(&ins.mode),
+            0x29 | 0x25 | 0x35 | 0x2D | 0x3D | 0x39 | 0x21 | 0x31 => self.and(value),
+            0x09 | 0x05 | 0x15 | 0x0D | 0x1D | 0x19 | 0x01 | 0x11 => self.ora(value),
+            0x49 | 0x45 | 0x55 | 0x4D | 0x5D | 0x59 | 0x41 | 0x51 => self.eor(value),
 
-            0x29 | 0x25 | 0x35 | 0x2D | 0x3D | 0x39 | 0x21 | 0x31 => self.and(&ins.mode),
-            0x09 | 0x05 | 0x15 | 0x0D | 0x1D | 0x19 | 0x01 | 0x11 => self.ora(&ins.mode),
-            0x49 | 0x45 | 0x55 | 0x4D | 0x5D | 0x59 | 0x41 | 0x51 => self.eor(&ins.mode),
             0xC9 | 0xC5 | 0xD5 | 0xCD | 0xDD | 0xD9 | 0xC1 | 0xD1 => {
-                self.compare(Register::A, &ins.mode)
+                self.compare(Register::A, value)
+            }
+            0xE0 | 0xE4 | 0xEC => self.compare(Register::X, value),
+            0xC0 | 0xC4 | 0xCC => self.compare(Register::Y, value),
+
+            0x24 | 0x2C => self.bit(value),
+
+            0x0B | 0x2B => self.anc(value),
+            0x4B => self.alr(value),
+            0x6B => self.arr(value),
+            0xCB => self.axs(value),
+            0x8B => self.xaa(value),
+
+            // the unofficial NOPs only read their operand
+            _ => (),
+        }
+    }
+
+    /// Performs the store of an `Access::Write` instruction.
+    fn write_op(&mut self, opcode: u8, addr: u16, page_crossed: bool) {
+        match opcode {
+            0x85 | 0x95 | 0x8D | 0x9D | 0x99 | 0x81 | 0x91 => self.write_byte(addr, self.a),
+            0x86 | 0x96 | 0x8E => self.write_byte(addr, self.x),
+            0x84 | 0x94 | 0x8C => self.write_byte(addr, self.y),
+            0x87 | 0x97 | 0x8F | 0x83 => self.write_byte(addr, self.a & self.x),
+
+            0x93 | 0x9F => self.unstable_store(addr, page_crossed, self.a & self.x, self.y),
+            0x9E => self.unstable_store(addr, page_crossed, self.x, self.y),
+            0x9C => self.unstable_store(addr, page_crossed, self.y, self.x),
+            0x9B => {
+                self.sp = self.a & self.x;
+                self.unstable_store(addr, page_crossed, self.sp, self.y)
+            }
+
+            _ => (),
+        }
+    }
+
+    /// Computes the value an `Access::ReadModifyWrite` instruction writes back.
+    fn modify_op(&mut self, opcode: u8, value: u8) -> u8 {
+        match opcode {
+            0x06 | 0x16 | 0x0E | 0x1E => self.asl(value),
+            0x46 | 0x56 | 0x4E | 0x5E => self.lsr(value),
+            0x26 | 0x36 | 0x2E | 0x3E => self.rol(value),
+            0x66 | 0x76 | 0x6E | 0x7E => self.ror(value),
+            0xE6 | 0xF6 | 0xEE | 0xFE => self.increment(value),
+            0xC6 | 0xD6 | 0xCE | 0xDE => self.decrement(value),
+
+            0x07 | 0x17 | 0x0F | 0x1F | 0x1B | 0x03 | 0x13 => self.slo(value),
+            0x27 | 0x37 | 0x2F | 0x3F | 0x3B | 0x33 | 0x23 => self.rla(value),
+            0x47 | 0x57 | 0x4F | 0x5F | 0x5B | 0x43 | 0x53 => self.sre(value),
+            0x67 | 0x77 | 0x6F | 0x7F | 0x7B | 0x63 | 0x73 => self.rra(value),
+            0xC7 | 0xD7 | 0xCF | 0xDF | 0xDB | 0xD3 | 0xC3 => self.dcp(value),
+            0xE7 | 0xF7 | 0xEF | 0xFF | 0xFB | 0xE3 | 0xF3 => self.isb(value),
+
+            _ => value,
+        }
+    }
+
+    /// Executes the opcodes that differ on the 65C02 and hands the rest,
+    /// which behave like their NMOS counterparts, to `execute`.
+    fn execute_cmos(&mut self, opcode: u8, ins: &Instruction) -> Option<StopReason> {
+        match opcode {
+            0x04 | 0x0C => self.tsb(&ins.mode),
+            0x14 | 0x1C => self.trb(&ins.mode),
+
+            // the (zp) instructions sit one opcode above their (zp),Y forms
+            0x12 | 0x32 | 0x52 | 0x72 | 0xB2 | 0xD2 | 0xF2 => {
+                let value = self.read_operand(&ins.mode);
+                self.read_op(opcode - 1, value);
+            }
+            0x92 => {
+                let (addr, _) = self.get_operand_address(&ins.mode, self.pc);
+                self.write_byte(addr, self.a);
+            }
+
+            0x1A => self.increment_register(Register::A),
+            0x3A => self.decrement_register(Register::A),
+
+            0x34 | 0x3C => {
+                let value = self.read_operand(&ins.mode);
+                self.bit(value);
+            }
+            0x89 => {
+                let value = self.read_operand(&ins.mode);
+                self.bit_immediate(value);
+            }
+
+            0x5A => self.push_register(Register::Y),
+            0x7A => self.pull_register(Register::Y),
+            0xDA => self.push_register(Register::X),
+            0xFA => self.pull_register(Register::X),
+
+            0x64 | 0x74 | 0x9C | 0x9E => self.stz(&ins.mode),
+
+            0x7C => self.jmp(&ins.mode),
+            0x80 => self.branch(BranchCondition::Always, &ins.mode),
+
+            0xCB => self.waiting = true,
+            0xDB => return Some(self.jam(opcode)),
+
+            0x07 | 0x17 | 0x27 | 0x37 | 0x47 | 0x57 | 0x67 | 0x77 => {
+                self.reset_memory_bit((opcode >> 4) & 0x07, &ins.mode)
+            }
+            0x87 | 0x97 | 0xA7 | 0xB7 | 0xC7 | 0xD7 | 0xE7 | 0xF7 => {
+                self.set_memory_bit((opcode >> 4) & 0x07, &ins.mode)
+            }
+            0x0F | 0x1F | 0x2F | 0x3F | 0x4F | 0x5F | 0x6F | 0x7F => {
+                self.branch_on_bit((opcode >> 4) & 0x07, false, &ins.mode)
+            }
+            0x8F | 0x9F | 0xAF | 0xBF | 0xCF | 0xDF | 0xEF | 0xFF => {
+                self.branch_on_bit((opcode >> 4) & 0x07, true, &ins.mode)
             }
 
-            0xC7 | 0xD7 | 0xCF | 0xDF | 0xDB | 0xD3 | 0xC3 => self.dcp(&ins.mode),
+            // the 65C02 takes an extra cycle for shifts and rotates only when
+            // the indexing crosses a page
+            0x1E | 0x3E | 0x5E | 0x7E => {
+                if self.get_operand_address(&ins.mode, self.pc).1 {
+                    self.cycles += 1;
+                }
+                return self.execute(opcode, ins);
+            }
 
-            0xE0 | 0xE4 | 0xEC => self.compare(Register::X, &ins.mode),
-            0xC0 | 0xC4 | 0xCC => self.compare(Register::Y, &ins.mode),
+            0x02 | 0x22 | 0x42 | 0x62 | 0x82 | 0xC2 | 0xE2 | 0x44 | 0x54 | 0xD4 | 0xF4 | 0x5C
+            | 0xDC | 0xFC => {
+                self.read_operand(&ins.mode);
+            }
+            0x03 | 0x13 | 0x23 | 0x33 | 0x43 | 0x53 | 0x63 | 0x73 | 0x83 | 0x93 | 0xA3 | 0xB3
+            | 0xC3 | 0xD3 | 0xE3 | 0xF3 | 0x0B | 0x1B | 0x2B | 0x3B | 0x4B | 0x5B | 0x6B | 0x7B
+            | 0x8B | 0x9B | 0xAB | 0xBB | 0xEB | 0xFB => (),
 
-            _ => todo!("{:?}", ins),
+            _ => return self.execute(opcode, ins),
         }
 
-        if pc == self.pc {
-            self.pc += (ins.bytes as u16) - 1;
+        None
+    }
+
+    fn check_faults(&mut self) -> Result<(), Error> {
+        let faults = self.fault.take().into_iter().chain(self.bus.take_faults());
+        for fault in faults {
+            match self.fault_policy.get(fault.kind()) {
+                Policy::Ignore => (),
+                Policy::Log => warn!("{}", fault),
+                Policy::Halt => return Err(fault),
+            }
         }
+        Ok(())
     }
 
     #[inline(always)]
@@ -360,26 +853,28 @@ impl Cpu {
         }
     }
 
-    pub(crate) fn get_operand_address(&self, mode: &AddressingMode, pc: u16) -> u16 {
+    pub(crate) fn get_operand_address(&self, mode: &AddressingMode, pc: u16) -> (u16, bool) {
         match mode {
-            AddressingMode::Immediate => pc,
-            AddressingMode::ZeroPage => self.read_byte(pc) as u16,
+            AddressingMode::Immediate => (pc, false),
+            AddressingMode::ZeroPage => (self.read_byte(pc) as u16, false),
             AddressingMode::ZeroPageX => {
                 let addr = self.read_byte(pc);
-                addr.wrapping_add(self.x) as u16
+                (addr.wrapping_add(self.x) as u16, false)
             }
             AddressingMode::ZeroPageY => {
                 let addr = self.read_byte(pc);
-                addr.wrapping_add(self.y) as u16
+                (addr.wrapping_add(self.y) as u16, false)
             }
-            AddressingMode::Absolute => self.read_word(pc),
+            AddressingMode::Absolute => (self.read_word(pc), false),
             AddressingMode::AbsoluteX => {
-                let addr = self.read_word(pc);
-                addr.wrapping_add(self.x as u16)
+                let base = self.read_word(pc);
+                let addr = base.wrapping_add(self.x as u16);
+                (addr, page_crossed(base, addr))
             }
             AddressingMode::AbsoluteY => {
-                let addr = self.read_word(pc);
-                addr.wrapping_add(self.y as u16)
+                let base = self.read_word(pc);
+                let addr = base.wrapping_add(self.y as u16);
+                (addr, page_crossed(base, addr))
             }
             AddressingMode::Indirect => {
                 let mem_address = self.read_word(pc);
@@ -388,36 +883,71 @@ impl Cpu {
                 // the result of JMP ($30FF) will be a transfer of control to $4080 rather than $5080 as you intended
                 // i.e. the 6502 took the low byte of the address from $30FF and the high byte from $3000
 
-                if mem_address & 0x00FF == 0x00FF {
+                // the 65C02 fixed this and reads the high byte from the next page
+                let addr = if mem_address & 0x00FF == 0x00FF && !self.variant.is_cmos() {
                     let lo = self.read_byte(mem_address);
                     let hi = self.read_byte(mem_address & 0xFF00);
                     (hi as u16) << 8 | (lo as u16)
                 } else {
                     self.read_word(mem_address)
-                }
+                };
+                (addr, false)
             }
             AddressingMode::IndirectX => {
                 let base = self.read_byte(pc);
                 let ptr = base.wrapping_add(self.x);
                 let lo = self.read_byte(ptr as u16) as u16;
                 let hi = self.read_byte(ptr.wrapping_add(1) as u16) as u16;
-                hi << 8 | lo
+                (hi << 8 | lo, false)
             }
             AddressingMode::IndirectY => {
                 let base = self.read_byte(pc);
                 let lo = self.read_byte(base as u16);
                 let hi = self.read_byte(base.wrapping_add(1) as u16);
                 let ptr = (hi as u16) << 8 | (lo as u16);
-                ptr.wrapping_add(self.y as u16)
+                let addr = ptr.wrapping_add(self.y as u16);
+                (addr, page_crossed(ptr, addr))
             }
             AddressingMode::Relative => {
+                // branch offsets are relative to the address of the next instruction
+                let next = pc.wrapping_add(1);
                 let offset = self.read_byte(pc) as i8;
-                pc.wrapping_add(offset as u16)
+                let addr = next.wrapping_add(offset as u16);
+                (addr, page_crossed(next, addr))
+            }
+            AddressingMode::ZeroPageIndirect => {
+                let base = self.read_byte(pc);
+                let lo = self.read_byte(base as u16);
+                let hi = self.read_byte(base.wrapping_add(1) as u16);
+                ((hi as u16) << 8 | (lo as u16), false)
+            }
+            AddressingMode::AbsoluteIndexedIndirect => {
+                let ptr = self.read_word(pc).wrapping_add(self.x as u16);
+                (self.read_word(ptr), false)
+            }
+            AddressingMode::ZeroPageRelative => (self.read_byte(pc) as u16, false),
+            AddressingMode::None => {
+                if self.fault.get().is_none() {
+                    self.fault.set(Some(Error::InvalidAddressingMode {
+                        mode: *mode,
+                        addr: pc,
+                    }));
+                }
+                (0, false)
             }
-            AddressingMode::None => unreachable!("addressing mode {:?} is not supported", mode),
         }
     }
 
+    /// Reads the operand of a read instruction, charging the extra cycle
+    /// indexed addressing takes when it crosses a page boundary.
+    fn read_operand(&mut self, mode: &AddressingMode) -> u8 {
+        let (addr, page_crossed) = self.get_operand_address(mode, self.pc);
+        if page_crossed {
+            self.cycles += 1;
+        }
+        self.read_byte(addr)
+    }
+
     fn transfer(&mut self, src: Register, dst: Register) {
         let value = self.get_register(src);
         self.set_register(dst, value);
@@ -438,66 +968,47 @@ impl Cpu {
         self.update_zero_and_negative(value);
     }
 
-    fn increment_memory(&mut self, mode: &AddressingMode) -> u8 {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr).wrapping_add(1);
-        self.write_byte(addr, value);
+    fn increment(&mut self, value: u8) -> u8 {
+        let value = value.wrapping_add(1);
         self.update_zero_and_negative(value);
         value
     }
 
-    fn decrement_memory(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr).wrapping_sub(1);
-        self.write_byte(addr, value);
+    fn decrement(&mut self, value: u8) -> u8 {
+        let value = value.wrapping_sub(1);
         self.update_zero_and_negative(value);
+        value
     }
 
-    fn and(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr);
+    fn and(&mut self, value: u8) {
         self.a &= value;
         self.update_zero_and_negative(self.a);
     }
 
-    fn ora(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr);
+    fn ora(&mut self, value: u8) {
         self.a |= value;
         self.update_zero_and_negative(self.a);
     }
 
-    fn eor(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr);
+    fn eor(&mut self, value: u8) {
         self.a ^= value;
         self.update_zero_and_negative(self.a);
     }
 
-    fn compare(&mut self, register: Register, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr);
+    fn compare(&mut self, register: Register, value: u8) {
         let register_value = self.get_register(register);
         self.status.carry = value <= register_value;
         self.update_zero_and_negative(register_value.wrapping_sub(value));
     }
 
-    fn store(&mut self, register: Register, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.get_register(register);
-        self.write_byte(addr, value);
-    }
-
-    fn bit(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr);
+    fn bit(&mut self, value: u8) {
         self.status.zero = value & self.a == 0;
         self.status.negative = value & 0x80 != 0;
         self.status.overflow = value & 0x40 != 0;
     }
 
-    fn branch(&mut self, condition: BranchCondition, mode: &AddressingMode) {
-        let condition_met = match condition {
+    fn condition_met(&self, condition: BranchCondition) -> bool {
+        match condition {
             BranchCondition::CarrySet => self.status.carry,
             BranchCondition::CarryClear => !self.status.carry,
             BranchCondition::ZeroSet => self.status.zero,
@@ -506,17 +1017,22 @@ impl Cpu {
             BranchCondition::MinusClear => !self.status.negative,
             BranchCondition::OverflowSet => self.status.overflow,
             BranchCondition::OverflowClear => !self.status.overflow,
-        };
-
-        if condition_met {
-            self.pc = self.get_operand_address(mode, self.pc);
+            BranchCondition::Always => true,
         }
+    }
 
-        self.pc += 1;
+    fn branch(&mut self, condition: BranchCondition, mode: &AddressingMode) {
+        if self.condition_met(condition) {
+            let (addr, page_crossed) = self.get_operand_address(mode, self.pc);
+            self.cycles += if page_crossed { 2 } else { 1 };
+            self.pc = addr;
+        } else {
+            self.pc = self.pc.wrapping_add(1);
+        }
     }
 
     fn jsr(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode, self.pc);
+        let (addr, _) = self.get_operand_address(mode, self.pc);
         self.push_word(self.pc.wrapping_add(1));
         self.pc = addr;
     }
@@ -548,20 +1064,21 @@ impl Cpu {
         hi << 8 | lo
     }
 
-    fn adc(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr);
-        self.add_to_a(value);
-    }
-
-    fn sbc(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr);
-        let value = ((value as i8).wrapping_neg().wrapping_sub(1)) as u8;
-        self.add_to_a(value);
+    fn decimal_mode(&self) -> bool {
+        self.status.decimal && self.variant.has_decimal_mode()
     }
 
     fn add_to_a(&mut self, value: u8) {
+        if self.decimal_mode() {
+            self.add_to_a_decimal(value);
+            if self.variant.is_cmos() {
+                // the 65C02 spends an extra cycle to fix up N and Z
+                self.update_zero_and_negative(self.a);
+                self.cycles += 1;
+            }
+            return;
+        }
+
         let carry = if self.status.carry { 1 } else { 0 };
         let sum = self.a as u16 + value as u16 + carry;
 
@@ -574,26 +1091,102 @@ impl Cpu {
         self.update_zero_and_negative(self.a);
     }
 
+    fn subtract_from_a(&mut self, value: u8) {
+        if self.decimal_mode() && self.variant.is_cmos() {
+            self.subtract_from_a_decimal_cmos(value);
+            self.cycles += 1;
+        } else if self.decimal_mode() {
+            self.subtract_from_a_decimal(value);
+        } else {
+            self.add_to_a(!value);
+        }
+    }
+
+    // NMOS decimal mode: Z is taken from the binary sum, N and V from the
+    // intermediate result before the high nibble is adjusted.
+    fn add_to_a_decimal(&mut self, value: u8) {
+        let a = self.a as u16;
+        let value = value as u16;
+        let carry = self.status.carry as u16;
+
+        let mut sum = (a & 0x0F) + (value & 0x0F) + carry;
+        if sum > 0x09 {
+            sum += 0x06;
+        }
+        sum = (sum & 0x0F) + (a & 0xF0) + (value & 0xF0) + if sum > 0x0F { 0x10 } else { 0 };
+
+        self.status.zero = (a + value + carry) & 0xFF == 0;
+        self.status.negative = sum & 0x80 != 0;
+        self.status.overflow = (a ^ sum) & 0x80 != 0 && (a ^ value) & 0x80 == 0;
+
+        if sum & 0x1F0 > 0x90 {
+            sum += 0x60;
+        }
+        self.status.carry = sum & 0xFF0 > 0xF0;
+        self.a = sum as u8;
+    }
+
+    // NMOS decimal mode: all flags are set from the binary difference.
+    fn subtract_from_a_decimal(&mut self, value: u8) {
+        let a = self.a as u16;
+        let value = value as u16;
+        let borrow = !self.status.carry as u16;
+
+        let binary = a.wrapping_sub(value).wrapping_sub(borrow);
+
+        let mut diff = (a & 0x0F).wrapping_sub(value & 0x0F).wrapping_sub(borrow);
+        diff = if diff & 0x10 != 0 {
+            (diff.wrapping_sub(0x06) & 0x0F)
+                | (a & 0xF0).wrapping_sub(value & 0xF0).wrapping_sub(0x10)
+        } else {
+            (diff & 0x0F) | (a & 0xF0).wrapping_sub(value & 0xF0)
+        };
+        if diff & 0x100 != 0 {
+            diff = diff.wrapping_sub(0x60);
+        }
+
+        self.status.carry = binary < 0x100;
+        self.update_zero_and_negative(binary as u8);
+        self.status.overflow = (a ^ binary) & 0x80 != 0 && (a ^ value) & 0x80 != 0;
+        self.a = diff as u8;
+    }
+
+    // 65C02 decimal mode: C and V come from the binary difference, N and Z
+    // from the adjusted result.
+    fn subtract_from_a_decimal_cmos(&mut self, value: u8) {
+        let a = self.a as i16;
+        let value = value as i16;
+        let borrow = !self.status.carry as i16;
+
+        let binary = a - value - borrow;
+        let lo = (a & 0x0F) - (value & 0x0F) - borrow;
+
+        let mut diff = binary;
+        if diff < 0 {
+            diff -= 0x60;
+        }
+        if lo < 0 {
+            diff -= 0x06;
+        }
+
+        self.status.carry = binary >= 0;
+        self.status.overflow = (a ^ binary) & 0x80 != 0 && (a ^ value) & 0x80 != 0;
+        self.a = diff as u8;
+        self.update_zero_and_negative(self.a);
+    }
+
     fn jmp(&mut self, mode: &AddressingMode) {
-        self.pc = self.get_operand_address(mode, self.pc);
+        let (addr, _) = self.get_operand_address(mode, self.pc);
+        self.pc = addr;
     }
 
-    fn lsr(&mut self, mode: &AddressingMode) -> u8 {
-        let addr = self.get_operand_address(mode, self.pc);
-        let mut value = self.read_byte(addr);
+    fn lsr(&mut self, value: u8) -> u8 {
         self.status.carry = value & 1 == 1;
-        value >>= 1;
-        self.write_byte(addr, value);
+        let value = value >> 1;
         self.update_zero_and_negative(value);
         value
     }
 
-    fn lsr_a(&mut self) {
-        self.status.carry = self.a & 1 == 1;
-        self.a >>= 1;
-        self.update_zero_and_negative(self.a);
-    }
-
     fn php(&mut self) {
         let mut status = self.status.clone();
         status.b1 = true;
@@ -616,6 +1209,25 @@ impl Cpu {
         self.update_zero_and_negative(self.a);
     }
 
+    fn brk(&mut self) -> StopReason {
+        let addr = self.pc.wrapping_sub(1);
+        // BRK is a two byte instruction, the byte after the opcode is skipped
+        self.pc = self.pc.wrapping_add(1);
+        self.interrupt(Interrupt::Brk);
+        StopReason::Brk(addr)
+    }
+
+    fn jam(&mut self, opcode: u8) -> StopReason {
+        self.pc = self.pc.wrapping_sub(1);
+        warn!("CPU jammed by opcode {:#04x} at {:#06x}", opcode, self.pc);
+        let jam = Jam {
+            opcode,
+            addr: self.pc,
+        };
+        self.jammed = Some(jam);
+        StopReason::Jammed(jam)
+    }
+
     fn rti(&mut self) {
         self.status = self.pop_byte().into();
         self.status.b1 = false;
@@ -623,105 +1235,782 @@ impl Cpu {
         self.pc = self.pop_word();
     }
 
-    fn asl_a(&mut self) {
-        let data = self.a;
-        self.status.carry = data >> 7 == 1;
-        self.a = data << 1;
-        self.update_zero_and_negative(self.a);
-    }
-
-    fn asl(&mut self, mode: &AddressingMode) -> u8 {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr);
+    fn asl(&mut self, value: u8) -> u8 {
         self.status.carry = value >> 7 == 1;
         let value = value << 1;
-        self.write_byte(addr, value);
         self.update_zero_and_negative(value);
         value
     }
 
-    fn ror_a(&mut self) {
-        let value = self.a;
-        let carry = if self.status.carry { 1 } else { 0 };
-        self.status.carry = value & 0x01 == 1;
-        self.a = (carry << 7) | (value >> 1);
-        self.update_zero_and_negative(self.a);
-    }
-
-    fn ror(&mut self, mode: &AddressingMode) -> u8 {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr);
+    fn ror(&mut self, value: u8) -> u8 {
         let carry = if self.status.carry { 1 } else { 0 };
         self.status.carry = value & 0x01 == 1;
         let value = (carry << 7) | (value >> 1);
-        self.write_byte(addr, value);
-        self.update_negative(value);
+        self.update_zero_and_negative(value);
         value
     }
 
-    fn rol_a(&mut self) {
-        let value = self.a;
+    fn rol(&mut self, value: u8) -> u8 {
         let carry = if self.status.carry { 1 } else { 0 };
         self.status.carry = value >> 7 == 1;
-        self.a = (value << 1) | carry;
-        self.update_zero_and_negative(self.a)
+        let value = (value << 1) | carry;
+        self.update_zero_and_negative(value);
+        value
     }
 
-    fn rol(&mut self, mode: &AddressingMode) -> u8 {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr);
-        let carry = if self.status.carry { 1 } else { 0 };
-        self.status.carry = value >> 7 == 1;
-        let value = (value << 1) | carry;
-        self.write_byte(addr, value);
-        self.update_negative(value);
+    fn lax(&mut self, value: u8) {
+        self.a = value;
+        self.x = value;
+        self.update_zero_and_negative(value);
+    }
+
+    fn dcp(&mut self, value: u8) -> u8 {
+        let value = value.wrapping_sub(1);
+        self.compare(Register::A, value);
         value
     }
 
-    fn lax(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode, self.pc);
-        self.a = self.read_byte(addr);
-        self.x = self.a;
-        self.update_zero_and_negative(self.a);
+    fn isb(&mut self, value: u8) -> u8 {
+        let value = value.wrapping_add(1);
+        self.subtract_from_a(value);
+        value
     }
 
-    fn sax(&mut self, mode: &AddressingMode) {
-        let value = self.a & self.x;
-        let addr = self.get_operand_address(mode, self.pc);
-        self.write_byte(addr, value);
+    fn slo(&mut self, value: u8) -> u8 {
+        let value = self.asl(value);
+        self.ora(value);
+        value
     }
 
-    fn dcp(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode, self.pc);
-        let value = self.read_byte(addr).wrapping_sub(1);
-        self.write_byte(addr, value);
-        self.status.carry = value <= self.a;
-        self.update_zero_and_negative(self.a.wrapping_sub(value));
+    fn rla(&mut self, value: u8) -> u8 {
+        let value = self.rol(value);
+        self.and(value);
+        value
+    }
+
+    fn sre(&mut self, value: u8) -> u8 {
+        let value = self.lsr(value);
+        self.eor(value);
+        value
     }
 
-    fn isb(&mut self, mode: &AddressingMode) {
-        let value = self.increment_memory(mode);
-        let value = ((value as i8).wrapping_neg().wrapping_sub(1)) as u8;
+    fn rra(&mut self, value: u8) -> u8 {
+        let value = self.ror(value);
         self.add_to_a(value);
+        value
     }
 
-    fn slo(&mut self, mode: &AddressingMode) {
-        self.a |= self.asl(mode);
+    fn anc(&mut self, value: u8) {
+        self.and(value);
+        self.status.carry = self.status.negative;
+    }
+
+    fn alr(&mut self, value: u8) {
+        self.a = self.lsr(self.a & value);
+    }
+
+    fn arr(&mut self, value: u8) {
+        self.a &= value;
+        let carry = if self.status.carry { 1 } else { 0 };
+        self.a = (carry << 7) | (self.a >> 1);
         self.update_zero_and_negative(self.a);
+        self.status.carry = self.a & 0x40 != 0;
+        self.status.overflow = ((self.a >> 6) ^ (self.a >> 5)) & 0x01 != 0;
+    }
+
+    fn axs(&mut self, value: u8) {
+        let and = self.a & self.x;
+        self.status.carry = value <= and;
+        self.x = and.wrapping_sub(value);
+        self.update_zero_and_negative(self.x);
     }
 
-    fn rla(&mut self, mode: &AddressingMode) {
-        self.a &= self.rol(mode);
+    fn xaa(&mut self, value: u8) {
+        self.a = (self.a | self.xaa_magic) & self.x & value;
         self.update_zero_and_negative(self.a);
     }
 
-    fn sre(&mut self, mode: &AddressingMode) {
-        self.a ^= self.lsr(mode);
+    fn lxa(&mut self, value: u8) {
+        self.a = (self.a | self.xaa_magic) & value;
+        self.x = self.a;
         self.update_zero_and_negative(self.a);
     }
 
-    fn rra(&mut self, mode: &AddressingMode) {
-        let value = self.ror(mode);
-        self.add_to_a(value);
+    fn las(&mut self, value: u8) {
+        let value = value & self.sp;
+        self.a = value;
+        self.x = value;
+        self.sp = value;
+        self.update_zero_and_negative(value);
+    }
+
+    /// Shared implementation of SHA/AHX, SHX, SHY and TAS. The stored value is
+    /// ANDed with the high byte of the base address plus one, and when the
+    /// indexing crosses a page that value also replaces the high byte of the
+    /// target address.
+    fn unstable_store(&mut self, addr: u16, page_crossed: bool, value: u8, index: u8) {
+        let base_hi = (addr.wrapping_sub(index as u16) >> 8) as u8;
+        let value = value & base_hi.wrapping_add(1);
+        let addr = if page_crossed {
+            (value as u16) << 8 | (addr & 0x00FF)
+        } else {
+            addr
+        };
+        self.write_byte(addr, value);
+    }
+
+    fn tsb(&mut self, mode: &AddressingMode) {
+        let (addr, _) = self.get_operand_address(mode, self.pc);
+        let value = self.read_byte(addr);
+        self.status.zero = value & self.a == 0;
+        self.write_byte(addr, value | self.a);
+    }
+
+    fn trb(&mut self, mode: &AddressingMode) {
+        let (addr, _) = self.get_operand_address(mode, self.pc);
+        let value = self.read_byte(addr);
+        self.status.zero = value & self.a == 0;
+        self.write_byte(addr, value & !self.a);
+    }
+
+    fn bit_immediate(&mut self, value: u8) {
+        self.status.zero = value & self.a == 0;
+    }
+
+    fn stz(&mut self, mode: &AddressingMode) {
+        let (addr, _) = self.get_operand_address(mode, self.pc);
+        self.write_byte(addr, 0);
+    }
+
+    fn push_register(&mut self, register: Register) {
+        self.push_byte(self.get_register(register));
+    }
+
+    fn pull_register(&mut self, register: Register) {
+        let value = self.pop_byte();
+        self.set_register(register, value);
+        self.update_zero_and_negative(value);
+    }
+
+    fn reset_memory_bit(&mut self, bit: u8, mode: &AddressingMode) {
+        let (addr, _) = self.get_operand_address(mode, self.pc);
+        let value = self.read_byte(addr) & !(1 << bit);
+        self.write_byte(addr, value);
+    }
+
+    fn set_memory_bit(&mut self, bit: u8, mode: &AddressingMode) {
+        let (addr, _) = self.get_operand_address(mode, self.pc);
+        let value = self.read_byte(addr) | (1 << bit);
+        self.write_byte(addr, value);
+    }
+
+    fn branch_on_bit(&mut self, bit: u8, set: bool, mode: &AddressingMode) {
+        let (addr, _) = self.get_operand_address(mode, self.pc);
+        let value = self.read_byte(addr);
+        let next = self.pc.wrapping_add(2);
+
+        if (value >> bit) & 0x01 == set as u8 {
+            let offset = self.read_byte(self.pc.wrapping_add(1)) as i8;
+            let target = next.wrapping_add(offset as u16);
+            self.cycles += if page_crossed(next, target) { 2 } else { 1 };
+            self.pc = target;
+        } else {
+            self.pc = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{asm::assemble_for, mem::Ram};
+
+    /// Assembles `source` at $0400 and runs it until it traps at the `done`
+    /// label.
+    fn run(variant: Variant, source: &str) -> Cpu<Ram> {
+        let program = assemble_for(variant, source, 0x0400).unwrap();
+        let mut ram = Ram::new();
+        ram.load(program.origin, &program.bytes);
+        let mut cpu = Cpu::new(ram);
+        cpu.variant = variant;
+        cpu.pc = program.origin;
+
+        // run hands control back on BRK, resume in its handler
+        let reason = loop {
+            match cpu.run_until_trap().unwrap() {
+                StopReason::Brk(_) => continue,
+                reason => break reason,
+            }
+        };
+        assert_eq!(reason, StopReason::Trapped(program.labels["done"]));
+        cpu
+    }
+
+    #[test]
+    fn multiply() {
+        let cpu = run(
+            Variant::Ricoh2A03,
+            "
+        product = $00
+        multiplicand = $02
+        multiplier = $04
+                lda #123
+                sta multiplicand
+                lda #45
+                sta multiplier
+                lda #0
+                sta product
+                sta product+1
+                sta multiplicand+1
+                ldx #8
+        loop:   lsr multiplier
+                bcc next
+                clc
+                lda product
+                adc multiplicand
+                sta product
+                lda product+1
+                adc multiplicand+1
+                sta product+1
+        next:   asl multiplicand
+                rol multiplicand+1
+                dex
+                bne loop
+        done:   jmp done
+            ",
+        );
+        assert_eq!(cpu.bus.data[0x00..0x02], (123u16 * 45).to_le_bytes());
+    }
+
+    #[test]
+    fn decimal_mode_depends_on_the_variant() {
+        let source = "
+                sed
+                clc
+                lda #$19
+                adc #$28
+                sta $00
+                sec
+                lda #$42
+                sbc #$13
+        done:   jmp done
+        ";
+
+        let cpu = run(Variant::Ricoh2A03, source);
+        assert_eq!((cpu.bus.data[0x00], cpu.a), (0x41, 0x2F));
+
+        let cpu = run(Variant::Nmos6502, source);
+        assert_eq!((cpu.bus.data[0x00], cpu.a), (0x47, 0x29));
+    }
+
+    #[test]
+    fn cmos_decimal_mode_sets_z_from_the_result_and_interrupts_clear_d() {
+        let source = "
+                sed
+                clc
+                lda #$99
+                adc #$01
+                php
+                sta $02
+                pla
+                sta $00
+                brk
+                .byte $EA
+        done:   jmp done
+        irq:    php
+                pla
+                sta $01
+                rti
+                .org $FFFE
+                .word irq
+        ";
+
+        // Z comes from the binary sum $9A on NMOS
+        let cpu = run(Variant::Nmos6502, source);
+        assert_eq!(cpu.bus.data[0x02], 0x00);
+        assert_eq!(cpu.bus.data[0x00] & 0x03, 0x01);
+        assert_eq!(cpu.bus.data[0x01] & 0x08, 0x08);
+
+        let cpu = run(Variant::Wdc65C02, source);
+        assert_eq!(cpu.bus.data[0x02], 0x00);
+        assert_eq!(cpu.bus.data[0x00] & 0x03, 0x03);
+        assert_eq!(cpu.bus.data[0x01] & 0x08, 0x00);
+    }
+
+    #[test]
+    fn cmos_bra_and_stz() {
+        let cpu = run(
+            Variant::Wdc65C02,
+            "
+                lda #$FF
+                sta $10
+                sta $11
+                bra skip
+                ldx #$01
+        skip:   stz $10
+                ldx #$01
+                stz $10,x
+        done:   jmp done
+            ",
+        );
+        assert_eq!(cpu.bus.data[0x10..0x12], [0x00, 0x00]);
+        assert_eq!(cpu.x, 0x01);
+    }
+
+    #[test]
+    fn cmos_bit_instructions() {
+        let cpu = run(
+            Variant::Wdc65C02,
+            "
+                lda #$3C
+                sta $10
+                sta $11
+                lda #$0F
+                trb $10
+                php
+                tsb $11
+                lda #$C0
+                tsb $12
+                php
+                rmb2 $11
+                smb7 $13
+                bbr7 $13,fail
+                bbs7 $13,taken
+        fail:   ldy #$FF
+        taken:  bbs0 $13,fail
+                bbr0 $13,done
+                ldy #$FE
+        done:   jmp done
+            ",
+        );
+        // TRB and TSB clear and set the bits of A, Z is set when none were set
+        assert_eq!(cpu.bus.data[0x10..0x14], [0x30, 0x3B, 0xC0, 0x80]);
+        assert_eq!(cpu.bus.data[0x01FD] & 0x02, 0x00);
+        assert_eq!(cpu.bus.data[0x01FC] & 0x02, 0x02);
+        assert_eq!(cpu.y, 0x00);
+    }
+
+    #[test]
+    fn jmp_indirect_crosses_the_page_only_on_the_65c02() {
+        let source = "
+                jmp ($05FF)
+                .org $0500
+                .byte $07
+                .org $05FF
+                .word $0800
+                .org $0700
+                ldx #$01
+                jmp done
+                .org $0800
+                ldx #$02
+        done:   jmp done
+        ";
+
+        assert_eq!(run(Variant::Nmos6502, source).x, 0x01);
+        assert_eq!(run(Variant::Wdc65C02, source).x, 0x02);
+    }
+
+    #[test]
+    fn brk_runs_the_irq_handler_and_returns_past_its_padding() {
+        let cpu = run(
+            Variant::Ricoh2A03,
+            "
+                ldx #$FF
+                txs
+                brk
+                .byte $EA
+                ldy #$01
+        done:   jmp done
+        irq:    lda #$42
+                rti
+                .org $FFFE
+                .word irq
+            ",
+        );
+        assert_eq!((cpu.a, cpu.y, cpu.sp), (0x42, 0x01, 0xFF));
+        // B is set in the status BRK pushed
+        assert_eq!(cpu.bus.data[0x01FD] & 0x30, 0x30);
+        assert_eq!(cpu.bus.data[0x01FE..0x0200], [0x05, 0x04]);
+    }
+
+    #[test]
+    fn step_stops_after_finishing_an_instruction_tick_started() {
+        let mut ram = Ram::new();
+        // lda #$01, lda #$02
+        ram.load(0x0400, &[0xA9, 0x01, 0xA9, 0x02]);
+        let mut cpu = Cpu::new(ram);
+        cpu.pc = 0x0400;
+
+        cpu.tick().unwrap();
+        assert_eq!(cpu.step().unwrap(), None);
+        assert!(cpu.at_instruction_boundary());
+        assert_eq!((cpu.a, cpu.pc, cpu.cycles), (0x01, 0x0402, 9));
+    }
+
+    /// A CPU at $0400 running NOPs, with the IRQ handler at $0600 and the
+    /// NMI handler at $0700, both NOPs too.
+    fn nops() -> Cpu<Ram> {
+        let mut ram = Ram::new();
+        ram.data[0x0400..0x0800].fill(0xEA);
+        ram.load(NMI_VECTOR, &[0x00, 0x07]);
+        ram.load(IRQ_VECTOR, &[0x00, 0x06]);
+        let mut cpu = Cpu::new(ram);
+        cpu.pc = 0x0400;
+        cpu
+    }
+
+    #[test]
+    fn nmi_fires_once_per_edge() {
+        let mut cpu = nops();
+        cpu.status.disable_interrupts = true;
+        cpu.set_nmi(true);
+
+        cpu.step().unwrap();
+        assert_eq!((cpu.pc, cpu.sp), (0x0700, 0xFA));
+        // B is clear in the pushed status, only the unused bit is set
+        assert_eq!(cpu.bus.data[0x01FB] & 0x30, 0x20);
+        assert_eq!(cpu.bus.data[0x01FC..0x01FE], [0x00, 0x04]);
+        assert!(cpu.status.disable_interrupts);
+
+        // the line is still held, but no new edge
+        cpu.step().unwrap();
+        assert_eq!(cpu.pc, 0x0701);
+
+        cpu.set_nmi(false);
+        cpu.set_nmi(true);
+        cpu.step().unwrap();
+        assert_eq!(cpu.pc, 0x0700);
+    }
+
+    #[test]
+    fn irq_fires_while_held_and_is_masked_by_i() {
+        let mut cpu = nops();
+        cpu.status.disable_interrupts = true;
+        cpu.set_irq(true);
+
+        cpu.step().unwrap();
+        assert_eq!(cpu.pc, 0x0401);
+
+        cpu.status.disable_interrupts = false;
+        cpu.step().unwrap();
+        assert_eq!((cpu.pc, cpu.sp), (0x0600, 0xFA));
+        assert_eq!(cpu.bus.data[0x01FB] & 0x30, 0x20);
+        assert_eq!(cpu.bus.data[0x01FC..0x01FE], [0x01, 0x04]);
+
+        // the handler runs with I set, clearing it lets the held line back in
+        cpu.step().unwrap();
+        assert_eq!(cpu.pc, 0x0601);
+        cpu.status.disable_interrupts = false;
+        cpu.step().unwrap();
+        assert_eq!((cpu.pc, cpu.sp), (0x0600, 0xF7));
+
+        cpu.set_irq(false);
+        cpu.status.disable_interrupts = false;
+        cpu.step().unwrap();
+        assert_eq!(cpu.pc, 0x0601);
+    }
+
+    #[test]
+    fn brk_pushes_b_and_uses_the_irq_vector() {
+        let mut cpu = nops();
+        cpu.bus.data[0x0400] = 0x00;
+
+        assert_eq!(cpu.step().unwrap(), Some(StopReason::Brk(0x0400)));
+        assert_eq!((cpu.pc, cpu.sp), (0x0600, 0xFA));
+        assert_eq!(cpu.bus.data[0x01FB] & 0x30, 0x30);
+        // the return address skips the padding byte
+        assert_eq!(cpu.bus.data[0x01FC..0x01FE], [0x02, 0x04]);
+        assert!(cpu.status.disable_interrupts);
+    }
+
+    /// Assembles `source` at `origin` and returns the cycles its first
+    /// instruction takes with X and Y set to 1.
+    fn cycles(origin: u16, source: &str) -> usize {
+        let program = assemble_for(Variant::Ricoh2A03, source, origin).unwrap();
+        let mut ram = Ram::new();
+        ram.load(program.origin, &program.bytes);
+        // ($10),Y points at $10FF
+        ram.load(0x0010, &[0xFF, 0x10]);
+        let mut cpu = Cpu::new(ram);
+        cpu.pc = program.origin;
+        cpu.x = 1;
+        cpu.y = 1;
+        cpu.status.zero = true;
+
+        let start = cpu.cycles;
+        cpu.step().unwrap();
+        cpu.cycles - start
+    }
+
+    #[test]
+    fn indexed_reads_take_a_cycle_more_across_a_page() {
+        assert_eq!(cycles(0x0400, "lda $1080,x"), 4);
+        assert_eq!(cycles(0x0400, "lda $10ff,x"), 5);
+        assert_eq!(cycles(0x0400, "lda $1080,y"), 4);
+        assert_eq!(cycles(0x0400, "lda $10ff,y"), 5);
+        assert_eq!(cycles(0x0400, "lda ($12),y"), 5);
+        assert_eq!(cycles(0x0400, "lda ($10),y"), 6);
+        // writes always take the extra cycle
+        assert_eq!(cycles(0x0400, "sta $1080,x"), 5);
+        assert_eq!(cycles(0x0400, "sta $10ff,x"), 5);
+    }
+
+    #[test]
+    fn branches_take_a_cycle_more_when_taken_and_another_across_a_page() {
+        assert_eq!(cycles(0x0400, "bne *+$10"), 2);
+        assert_eq!(cycles(0x0400, "beq *+$10"), 3);
+        assert_eq!(cycles(0x04F0, "beq *+$20"), 4);
+        assert_eq!(cycles(0x0410, "beq *-$20"), 4);
+    }
+
+    /// Assembles `source` at $0400, lets `setup` prepare the CPU and steps
+    /// over the first instruction.
+    fn execute<F>(source: &str, setup: F) -> Cpu<Ram>
+    where
+        F: FnOnce(&mut Cpu<Ram>),
+    {
+        let program = assemble_for(Variant::Ricoh2A03, source, 0x0400).unwrap();
+        let mut ram = Ram::new();
+        ram.load(program.origin, &program.bytes);
+        let mut cpu = Cpu::new(ram);
+        cpu.pc = program.origin;
+        setup(&mut cpu);
+        cpu.step().unwrap();
+        cpu
+    }
+
+    #[test]
+    fn anc_copies_n_into_c() {
+        let cpu = execute("anc #$80", |cpu| cpu.a = 0xC1);
+        assert_eq!(cpu.a, 0x80);
+        assert!(cpu.status.negative && cpu.status.carry && !cpu.status.zero);
+
+        let cpu = execute("anc #$01", |cpu| cpu.a = 0xC1);
+        assert_eq!(cpu.a, 0x01);
+        assert!(!cpu.status.negative && !cpu.status.carry);
+    }
+
+    #[test]
+    fn alr_ands_then_shifts_right() {
+        let cpu = execute("alr #$03", |cpu| cpu.a = 0xFF);
+        assert_eq!(cpu.a, 0x01);
+        assert!(cpu.status.carry && !cpu.status.negative && !cpu.status.zero);
+    }
+
+    #[test]
+    fn arr_takes_c_from_bit_6_and_v_from_bits_6_and_5() {
+        // (A, carry in) -> (A, C, V)
+        for (a, carry, expected) in [
+            (0xFF, false, (0x7F, true, false)),
+            (0xFF, true, (0xFF, true, false)),
+            (0x40, false, (0x20, false, true)),
+            (0x80, false, (0x40, true, true)),
+            (0x01, false, (0x00, false, false)),
+        ] {
+            let cpu = execute("arr #$ff", |cpu| {
+                cpu.a = a;
+                cpu.status.carry = carry;
+            });
+            assert_eq!(
+                (cpu.a, cpu.status.carry, cpu.status.overflow),
+                expected,
+                "A={:02X} C={}",
+                a,
+                carry
+            );
+            assert_eq!(cpu.status.negative, expected.0 & 0x80 != 0);
+            assert_eq!(cpu.status.zero, expected.0 == 0);
+        }
+    }
+
+    #[test]
+    fn axs_subtracts_from_a_and_x_without_borrow() {
+        let setup = |cpu: &mut Cpu<Ram>| {
+            cpu.a = 0x0F;
+            cpu.x = 0xF3;
+            cpu.status.carry = false;
+        };
+        let cpu = execute("axs #$02", setup);
+        assert_eq!((cpu.x, cpu.a), (0x01, 0x0F));
+        assert!(cpu.status.carry && !cpu.status.negative);
+
+        let cpu = execute("axs #$05", setup);
+        assert_eq!(cpu.x, 0xFE);
+        assert!(!cpu.status.carry && cpu.status.negative);
+    }
+
+    #[test]
+    fn las_ands_memory_with_sp_into_a_x_and_sp() {
+        let cpu = execute("las $1000,y", |cpu| {
+            cpu.y = 1;
+            cpu.bus.data[0x1001] = 0xF3;
+        });
+        assert_eq!((cpu.a, cpu.x, cpu.sp), (0xF1, 0xF1, 0xF1));
+        assert!(cpu.status.negative);
+    }
+
+    #[test]
+    fn unstable_stores_and_with_the_high_byte_plus_one() {
+        let cpu = execute("ahx $1000,y", |cpu| {
+            (cpu.a, cpu.x, cpu.y) = (0xFF, 0x1F, 1);
+        });
+        assert_eq!(cpu.bus.data[0x1001], 0x11);
+
+        let cpu = execute("ahx ($10),y", |cpu| {
+            (cpu.a, cpu.x, cpu.y) = (0x1F, 0xFF, 1);
+            cpu.bus.load(0x0010, &[0x00, 0x10]);
+        });
+        assert_eq!(cpu.bus.data[0x1001], 0x11);
+
+        let cpu = execute("shx $1000,y", |cpu| (cpu.x, cpu.y) = (0xFF, 1));
+        assert_eq!(cpu.bus.data[0x1001], 0x11);
+
+        let cpu = execute("shy $1000,x", |cpu| (cpu.x, cpu.y) = (1, 0xFF));
+        assert_eq!(cpu.bus.data[0x1001], 0x11);
+    }
+
+    #[test]
+    fn unstable_stores_crossing_a_page_replace_the_high_byte() {
+        let cpu = execute("shx $10ff,y", |cpu| (cpu.x, cpu.y) = (0x03, 1));
+        assert_eq!(cpu.bus.data[0x1100], 0x00);
+        assert_eq!(cpu.bus.data[0x0100], 0x01);
+
+        let cpu = execute("ahx $10ff,y", |cpu| {
+            (cpu.a, cpu.x, cpu.y) = (0x07, 0xFF, 1);
+        });
+        assert_eq!(cpu.bus.data[0x0100], 0x01);
+    }
+
+    #[test]
+    fn tas_sets_sp_to_a_and_x_before_storing() {
+        let cpu = execute("tas $1000,y", |cpu| {
+            (cpu.a, cpu.x, cpu.y) = (0xF3, 0x3F, 1);
+        });
+        assert_eq!(cpu.sp, 0x33);
+        assert_eq!(cpu.bus.data[0x1001], 0x11);
+    }
+
+    #[test]
+    fn xaa_ors_a_with_the_magic_constant() {
+        let setup = |cpu: &mut Cpu<Ram>| (cpu.a, cpu.x) = (0x00, 0x0F);
+        let cpu = execute("xaa #$ff", setup);
+        assert_eq!(cpu.a, XAA_MAGIC & 0x0F);
+        assert!(!cpu.status.zero);
+
+        let cpu = execute("xaa #$ff", |cpu| {
+            setup(cpu);
+            cpu.xaa_magic = 0x00;
+        });
+        assert_eq!(cpu.a, 0x00);
+        assert!(cpu.status.zero);
+    }
+
+    #[test]
+    fn jam_holds_the_pc_until_reset() {
+        let mut ram = Ram::new();
+        ram.load(0x0400, &[0x02, 0xEA]);
+        ram.load(RESET_VECTOR, &[0x00, 0x05]);
+        ram.data[0x0500] = 0xEA;
+        let mut cpu = Cpu::new(ram);
+        cpu.pc = 0x0400;
+
+        let jam = Jam {
+            opcode: 0x02,
+            addr: 0x0400,
+        };
+        assert_eq!(cpu.step().unwrap(), Some(StopReason::Jammed(jam)));
+        assert_eq!(cpu.jammed, Some(jam));
+        assert_eq!(cpu.pc, 0x0400);
+
+        let cycles = cpu.cycles;
+        assert_eq!(cpu.step().unwrap(), Some(StopReason::Jammed(jam)));
+        assert_eq!(cpu.run().unwrap(), StopReason::Jammed(jam));
+        assert_eq!((cpu.pc, cpu.cycles), (0x0400, cycles));
+
+        cpu.reset();
+        assert_eq!(cpu.jammed, None);
+        assert_eq!(cpu.pc, 0x0500);
+        assert_eq!(cpu.step().unwrap(), None);
+        assert_eq!(cpu.pc, 0x0501);
+    }
+
+    #[test]
+    fn run_for_cycles_reports_the_overshoot() {
+        let mut cpu = nops();
+        let start = cpu.cycles;
+
+        // NOPs take 2 cycles, 3 of them are needed to spend 5
+        assert_eq!(
+            cpu.run_for_cycles(5).unwrap(),
+            StopReason::CycleBudgetExhausted { overshoot: 1 }
+        );
+        assert_eq!((cpu.pc, cpu.cycles), (0x0403, start + 6));
+
+        assert_eq!(
+            cpu.run_for_cycles(4).unwrap(),
+            StopReason::CycleBudgetExhausted { overshoot: 0 }
+        );
+        assert_eq!((cpu.pc, cpu.cycles), (0x0405, start + 10));
+
+        assert_eq!(
+            cpu.run_for_cycles(0).unwrap(),
+            StopReason::CycleBudgetExhausted { overshoot: 0 }
+        );
+        assert_eq!(cpu.pc, 0x0405);
+    }
+
+    #[test]
+    fn run_until_pc_stops_before_the_target_runs() {
+        let program = assemble_for(
+            Variant::Ricoh2A03,
+            "
+                ldx #$01
+                jmp skip
+                inx
+        skip:   ldy #$01
+        target: lda #$01
+            ",
+            0x0400,
+        )
+        .unwrap();
+        let mut ram = Ram::new();
+        ram.load(program.origin, &program.bytes);
+        let mut cpu = Cpu::new(ram);
+        cpu.pc = program.origin;
+
+        assert_eq!(
+            cpu.run_until_pc(program.labels["target"]).unwrap(),
+            StopReason::ConditionMet
+        );
+        assert_eq!(cpu.pc, program.labels["target"]);
+        assert_eq!((cpu.a, cpu.x, cpu.y), (0x00, 0x01, 0x01));
+    }
+
+    #[test]
+    fn every_opcode_of_every_variant_executes() {
+        for variant in [Variant::Ricoh2A03, Variant::Nmos6502, Variant::Wdc65C02] {
+            for ins in variant.instructions() {
+                let mut ram = Ram::new();
+                // operands point into the zero page, vectors at $0000
+                ram.load(0x0400, &[ins.opcode, 0x10, 0x00]);
+                ram.data[0x0000] = 0xEA;
+                let mut cpu = Cpu::new(ram);
+                cpu.variant = variant;
+                cpu.pc = 0x0400;
+
+                let start = cpu.cycles;
+                let stop = cpu.step().unwrap();
+                let context = format!("{:?} {:02X} {}", variant, ins.opcode, ins.mnemonic);
+                match stop {
+                    Some(StopReason::Jammed(jam)) => {
+                        assert_eq!(jam.opcode, ins.opcode, "{}", context)
+                    }
+                    _ => {
+                        assert_ne!(cpu.pc, 0x0400, "{}", context);
+                        assert!(cpu.cycles - start >= ins.cycles, "{}", context);
+                    }
+                }
+            }
+        }
     }
 }
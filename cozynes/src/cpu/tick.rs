@@ -0,0 +1,712 @@
+use super::{
+    page_crossed, AddressingMode, BranchCondition, Cpu, Interrupt, StopReason, IRQ_VECTOR,
+    NMI_VECTOR, STACK,
+};
+use crate::{
+    instruction::{Access, Instruction, INSTRUCTIONS},
+    mem::Mem,
+    Error,
+};
+
+/// How far `Cpu::tick` has got through the current instruction.
+#[derive(Debug, Default, Clone, Copy)]
+pub(super) struct TickState {
+    /// Cycles spent on the current instruction, 0 between instructions.
+    cycle: u8,
+    opcode: u8,
+    origin: u16,
+    interrupt: Option<Interrupt>,
+    /// Set once the effective address is known.
+    addressed: bool,
+    data_cycle: u8,
+    addr: u16,
+    ptr: u8,
+    value: u8,
+    page_crossed: bool,
+    /// The interrupt seen at the start of the latest cycle. Interrupts are
+    /// polled before the last cycle of an instruction, so once the
+    /// instruction completes this is what the next fetch services.
+    polled: Option<Interrupt>,
+    /// Set when `polled` was sampled by a ticked instruction, otherwise the
+    /// fetch polls for itself.
+    sampled: bool,
+    /// Cycles left of an instruction that was executed in one go by `step`.
+    stall: usize,
+}
+
+impl TickState {
+    pub(super) fn in_progress(&self) -> bool {
+        self.cycle != 0 || self.stall != 0
+    }
+
+    /// Whether the interrupt lines are sampled at the start of this cycle in
+    /// case it is the last one. The interrupt sequence never polls, so the
+    /// first instruction of a handler always runs, and a taken branch that
+    /// stays on its page keeps the sample from before its second cycle.
+    fn samples_interrupts(&self) -> bool {
+        match self.opcode {
+            0x00 => false,
+            0x10 | 0x30 | 0x50 | 0x70 | 0x90 | 0xB0 | 0xD0 | 0xF0 => self.cycle != 3,
+            _ => true,
+        }
+    }
+
+    fn index(&mut self, base: u16, index: u8, access: Access) {
+        self.addr = base.wrapping_add(index as u16);
+        self.page_crossed = page_crossed(base, self.addr);
+        self.addressed = access == Access::Read && !self.page_crossed;
+    }
+}
+
+impl<M: Mem> Cpu<M> {
+    /// Advances the CPU by a single cycle, performing exactly the bus access
+    /// the 2A03 makes in that cycle, including the dummy reads and writes.
+    /// Returns `Some` on the cycle that completes an instruction `step` would
+    /// stop at.
+    ///
+    /// The 65C02 has different dummy accesses which are not modelled, so its
+    /// instructions run on the first cycle and the rest are spent idle.
+    pub fn tick(&mut self) -> Result<Option<StopReason>, Error> {
+        // the bus was already clocked by the `step` that ran the instruction
+        if self.ticks.stall > 0 {
+            self.ticks.stall -= 1;
+            self.cycles += 1;
+            return Ok(None);
+        }
+
+        if self.ticks.cycle == 0 {
+            if let Some(jam) = self.jammed {
+                return Ok(Some(StopReason::Jammed(jam)));
+            }
+
+            if self.variant.is_cmos() {
+                let start = self.cycles;
+                let stop = self.step()?;
+                self.ticks.stall = (self.cycles - start).saturating_sub(1);
+                self.cycles = start + 1;
+                return Ok(stop);
+            }
+        }
+
+        let mut t = self.ticks;
+        t.cycle += 1;
+        let stop = if t.cycle == 1 {
+            self.tick_fetch(&mut t);
+            None
+        } else {
+            if t.samples_interrupts() {
+                t.polled = self.pending_interrupt();
+            }
+            self.tick_instruction(&mut t)
+        };
+        self.ticks = if t.cycle == 0 {
+            TickState {
+                polled: t.polled,
+                sampled: true,
+                ..Default::default()
+            }
+        } else {
+            t
+        };
+
+        self.cycles += 1;
+        self.bus.clock(1);
+        self.check_faults()?;
+        Ok(stop)
+    }
+
+    /// Returns true when no instruction is partway through being ticked.
+    pub fn at_instruction_boundary(&self) -> bool {
+        !self.ticks.in_progress()
+    }
+
+    fn tick_fetch(&mut self, t: &mut TickState) {
+        t.origin = self.pc;
+        t.interrupt = if t.sampled {
+            t.polled
+        } else {
+            self.pending_interrupt()
+        };
+        t.polled = None;
+        if t.interrupt == Some(Interrupt::Nmi) {
+            self.nmi_pending = false;
+        }
+
+        if let Some(interrupt) = t.interrupt {
+            trace!("{}, {:?}", self, interrupt);
+            // the opcode is fetched and thrown away
+            self.read_byte(self.pc);
+            t.opcode = 0x00;
+            return;
+        }
+
+        t.opcode = self.read_byte(self.pc);
+        self.record_history(t.opcode);
+        self.pc = self.pc.wrapping_add(1);
+
+        let ins = &INSTRUCTIONS[t.opcode as usize];
+        trace!("{}, {:?}", self, ins);
+        if ins.mode == AddressingMode::Immediate {
+            t.addr = self.pc;
+            t.addressed = true;
+            self.pc = self.pc.wrapping_add(1);
+        }
+    }
+
+    fn tick_instruction(&mut self, t: &mut TickState) -> Option<StopReason> {
+        let ins = &INSTRUCTIONS[t.opcode as usize];
+
+        match t.opcode {
+            0x00 => return self.tick_interrupt(t),
+            0x02 | 0x12 | 0x22 | 0x32 | 0x42 | 0x52 | 0x62 | 0x72 | 0x92 | 0xB2 | 0xD2 | 0xF2 => {
+                self.read_byte(self.pc);
+                t.cycle = 0;
+                return Some(self.jam(t.opcode));
+            }
+            0x20 => self.tick_jsr(t),
+            0x40 => self.tick_rti(t),
+            0x60 => self.tick_rts(t),
+            0x08 | 0x48 => self.tick_push(t),
+            0x28 | 0x68 => self.tick_pull(t),
+            0x4C | 0x6C => self.tick_jmp(t),
+            0x10 | 0x30 | 0x50 | 0x70 | 0x90 | 0xB0 | 0xD0 | 0xF0 => self.tick_branch(t),
+            _ if ins.access == Access::None => {
+                self.read_byte(self.pc);
+                self.execute(t.opcode, ins);
+                t.cycle = 0;
+            }
+            _ if !t.addressed => self.tick_address(t, ins),
+            _ => self.tick_data(t, ins),
+        }
+
+        None
+    }
+
+    fn tick_interrupt(&mut self, t: &mut TickState) -> Option<StopReason> {
+        match t.cycle {
+            2 => {
+                self.read_byte(self.pc);
+                if t.interrupt.is_none() {
+                    // BRK is a two byte instruction, the byte after the opcode is skipped
+                    self.pc = self.pc.wrapping_add(1);
+                    t.interrupt = Some(Interrupt::Brk);
+                }
+            }
+            3 => self.push_byte((self.pc >> 8) as u8),
+            4 => self.push_byte(self.pc as u8),
+            5 => {
+                let interrupt = t.interrupt.unwrap_or(Interrupt::Brk);
+                // an NMI arriving before the vector is fetched hijacks BRK and IRQ
+                t.addr = if interrupt == Interrupt::Nmi || self.nmi_pending {
+                    self.nmi_pending = false;
+                    NMI_VECTOR
+                } else {
+                    IRQ_VECTOR
+                };
+
+                let mut status = self.status.clone();
+                status.b1 = interrupt == Interrupt::Brk;
+                status.b2 = true;
+                self.push_byte(status.into());
+                self.status.disable_interrupts = true;
+            }
+            6 => t.value = self.read_byte(t.addr),
+            _ => {
+                let hi = self.read_byte(t.addr.wrapping_add(1)) as u16;
+                self.pc = hi << 8 | t.value as u16;
+                t.cycle = 0;
+                if t.interrupt == Some(Interrupt::Brk) {
+                    return Some(StopReason::Brk(t.origin));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn tick_jsr(&mut self, t: &mut TickState) {
+        match t.cycle {
+            2 => {
+                t.value = self.read_byte(self.pc);
+                self.pc = self.pc.wrapping_add(1);
+            }
+            3 => {
+                self.read_byte(STACK + self.sp as u16);
+            }
+            4 => self.push_byte((self.pc >> 8) as u8),
+            5 => self.push_byte(self.pc as u8),
+            _ => {
+                let hi = self.read_byte(self.pc) as u16;
+                self.pc = hi << 8 | t.value as u16;
+                t.cycle = 0;
+            }
+        }
+    }
+
+    fn tick_rts(&mut self, t: &mut TickState) {
+        match t.cycle {
+            2 => {
+                self.read_byte(self.pc);
+            }
+            3 => {
+                self.read_byte(STACK + self.sp as u16);
+            }
+            4 => t.value = self.pop_byte(),
+            5 => {
+                let hi = self.pop_byte() as u16;
+                self.pc = hi << 8 | t.value as u16;
+            }
+            _ => {
+                self.read_byte(self.pc);
+                self.pc = self.pc.wrapping_add(1);
+                t.cycle = 0;
+            }
+        }
+    }
+
+    fn tick_rti(&mut self, t: &mut TickState) {
+        match t.cycle {
+            2 => {
+                self.read_byte(self.pc);
+            }
+            3 => {
+                self.read_byte(STACK + self.sp as u16);
+            }
+            4 => {
+                self.status = self.pop_byte().into();
+                self.status.b1 = false;
+                self.status.b2 = true;
+            }
+            5 => t.value = self.pop_byte(),
+            _ => {
+                let hi = self.pop_byte() as u16;
+                self.pc = hi << 8 | t.value as u16;
+                t.cycle = 0;
+            }
+        }
+    }
+
+    fn tick_push(&mut self, t: &mut TickState) {
+        match t.cycle {
+            2 => {
+                self.read_byte(self.pc);
+            }
+            _ => {
+                if t.opcode == 0x08 {
+                    self.php();
+                } else {
+                    self.pha();
+                }
+                t.cycle = 0;
+            }
+        }
+    }
+
+    fn tick_pull(&mut self, t: &mut TickState) {
+        match t.cycle {
+            2 => {
+                self.read_byte(self.pc);
+            }
+            3 => {
+                self.read_byte(STACK + self.sp as u16);
+            }
+            _ => {
+                if t.opcode == 0x28 {
+                    self.plp();
+                } else {
+                    self.pla();
+                }
+                t.cycle = 0;
+            }
+        }
+    }
+
+    fn tick_jmp(&mut self, t: &mut TickState) {
+        match t.cycle {
+            2 => {
+                t.value = self.read_byte(self.pc);
+                self.pc = self.pc.wrapping_add(1);
+            }
+            3 => {
+                let hi = self.read_byte(self.pc) as u16;
+                if t.opcode == 0x4C {
+                    self.pc = hi << 8 | t.value as u16;
+                    t.cycle = 0;
+                } else {
+                    t.addr = hi << 8 | t.value as u16;
+                }
+            }
+            4 => t.value = self.read_byte(t.addr),
+            _ => {
+                // the pointer's high byte is read without carrying into the next page
+                let addr = (t.addr & 0xFF00) | (t.addr.wrapping_add(1) & 0x00FF);
+                let hi = self.read_byte(addr) as u16;
+                self.pc = hi << 8 | t.value as u16;
+                t.cycle = 0;
+            }
+        }
+    }
+
+    fn tick_branch(&mut self, t: &mut TickState) {
+        match t.cycle {
+            2 => {
+                t.value = self.read_byte(self.pc);
+                self.pc = self.pc.wrapping_add(1);
+                if !self.condition_met(BranchCondition::from_opcode(t.opcode)) {
+                    t.cycle = 0;
+                }
+            }
+            3 => {
+                self.read_byte(self.pc);
+                t.addr = self.pc.wrapping_add(t.value as i8 as u16);
+                // the offset is added to the low byte first, fixing up the
+                // high byte takes another cycle
+                if page_crossed(self.pc, t.addr) {
+                    self.pc = (self.pc & 0xFF00) | (t.addr & 0x00FF);
+                } else {
+                    self.pc = t.addr;
+                    t.cycle = 0;
+                }
+            }
+            _ => {
+                self.read_byte(self.pc);
+                self.pc = t.addr;
+                t.cycle = 0;
+            }
+        }
+    }
+
+    /// Runs one cycle of working out the effective address of a memory
+    /// instruction. Reads that don't cross a page skip the dummy read of the
+    /// unfixed address, writes and read-modify-writes always make it.
+    fn tick_address(&mut self, t: &mut TickState, ins: &Instruction) {
+        let index = match ins.mode {
+            AddressingMode::ZeroPageY | AddressingMode::AbsoluteY | AddressingMode::IndirectY => {
+                self.y
+            }
+            _ => self.x,
+        };
+
+        match (ins.mode, t.cycle) {
+            (AddressingMode::ZeroPage, _) => {
+                t.addr = self.read_byte(self.pc) as u16;
+                self.pc = self.pc.wrapping_add(1);
+                t.addressed = true;
+            }
+            (
+                AddressingMode::ZeroPageX | AddressingMode::ZeroPageY | AddressingMode::IndirectX,
+                2,
+            )
+            | (AddressingMode::IndirectY, 2) => {
+                t.ptr = self.read_byte(self.pc);
+                self.pc = self.pc.wrapping_add(1);
+            }
+            (AddressingMode::ZeroPageX | AddressingMode::ZeroPageY, _) => {
+                self.read_byte(t.ptr as u16);
+                t.addr = t.ptr.wrapping_add(index) as u16;
+                t.addressed = true;
+            }
+            (
+                AddressingMode::Absolute | AddressingMode::AbsoluteX | AddressingMode::AbsoluteY,
+                2,
+            ) => {
+                t.value = self.read_byte(self.pc);
+                self.pc = self.pc.wrapping_add(1);
+            }
+            (AddressingMode::Absolute, _) => {
+                let hi = self.read_byte(self.pc) as u16;
+                self.pc = self.pc.wrapping_add(1);
+                t.addr = hi << 8 | t.value as u16;
+                t.addressed = true;
+            }
+            (AddressingMode::AbsoluteX | AddressingMode::AbsoluteY, 3) => {
+                let hi = self.read_byte(self.pc) as u16;
+                self.pc = self.pc.wrapping_add(1);
+                t.index(hi << 8 | t.value as u16, index, ins.access);
+            }
+            (AddressingMode::IndirectX, 3) => {
+                self.read_byte(t.ptr as u16);
+                t.ptr = t.ptr.wrapping_add(index);
+            }
+            (AddressingMode::IndirectX, 4) | (AddressingMode::IndirectY, 3) => {
+                t.value = self.read_byte(t.ptr as u16);
+            }
+            (AddressingMode::IndirectX, _) => {
+                let hi = self.read_byte(t.ptr.wrapping_add(1) as u16) as u16;
+                t.addr = hi << 8 | t.value as u16;
+                t.addressed = true;
+            }
+            (AddressingMode::IndirectY, 4) => {
+                let hi = self.read_byte(t.ptr.wrapping_add(1) as u16) as u16;
+                t.index(hi << 8 | t.value as u16, index, ins.access);
+            }
+            (
+                AddressingMode::AbsoluteX | AddressingMode::AbsoluteY | AddressingMode::IndirectY,
+                _,
+            ) => {
+                // the address before the carry into the high byte is fixed up
+                let unfixed = if t.page_crossed {
+                    t.addr.wrapping_sub(0x100)
+                } else {
+                    t.addr
+                };
+                self.read_byte(unfixed);
+                t.addressed = true;
+            }
+            _ => t.addressed = true,
+        }
+    }
+
+    fn tick_data(&mut self, t: &mut TickState, ins: &Instruction) {
+        match (ins.access, t.data_cycle) {
+            (Access::Read, _) => {
+                let value = self.read_byte(t.addr);
+                self.read_op(t.opcode, value);
+                t.cycle = 0;
+            }
+            (Access::Write, _) => {
+                self.write_op(t.opcode, t.addr, t.page_crossed);
+                t.cycle = 0;
+            }
+            (_, 0) => t.value = self.read_byte(t.addr),
+            (_, 1) => {
+                // the unmodified value is written back while the ALU works
+                self.write_byte(t.addr, t.value);
+                t.value = self.modify_op(t.opcode, t.value);
+            }
+            _ => {
+                self.write_byte(t.addr, t.value);
+                t.cycle = 0;
+            }
+        }
+        t.data_cycle += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::{asm::assemble, mem::Ram};
+
+    /// A bus access made by the CPU.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Cycle {
+        Read(u16),
+        Write(u16, u8),
+    }
+
+    use Cycle::{Read, Write};
+
+    /// RAM that records every access made to it.
+    struct Recorder {
+        ram: Ram,
+        log: RefCell<Vec<Cycle>>,
+    }
+
+    impl Mem for Recorder {
+        fn read_byte(&self, addr: u16) -> u8 {
+            self.log.borrow_mut().push(Read(addr));
+            self.ram.read_byte(addr)
+        }
+
+        fn write_byte(&mut self, addr: u16, value: u8) {
+            self.log.borrow_mut().push(Write(addr, value));
+            self.ram.write_byte(addr, value);
+        }
+    }
+
+    const IRQ_HANDLER: u16 = 0x0600;
+    const NMI_HANDLER: u16 = 0x0700;
+
+    fn cpu_at(origin: u16, source: &str) -> Cpu<Recorder> {
+        let program = assemble(source, origin).unwrap();
+        let mut ram = Ram::new();
+        ram.load(program.origin, &program.bytes);
+        ram.load(IRQ_VECTOR, &IRQ_HANDLER.to_le_bytes());
+        ram.load(NMI_VECTOR, &NMI_HANDLER.to_le_bytes());
+        // the handlers are NOPs
+        ram.data[IRQ_HANDLER as usize] = 0xEA;
+        ram.data[NMI_HANDLER as usize] = 0xEA;
+        let mut cpu = Cpu::new(Recorder {
+            ram,
+            log: RefCell::new(Vec::new()),
+        });
+        cpu.pc = program.origin;
+        cpu.bus.log.borrow_mut().clear();
+        cpu
+    }
+
+    /// Ticks through one instruction, checking that every cycle makes
+    /// exactly one bus access, and returns the accesses.
+    fn tick_instruction(cpu: &mut Cpu<Recorder>) -> Vec<Cycle> {
+        cpu.bus.log.borrow_mut().clear();
+        let mut cycles = Vec::new();
+        loop {
+            cpu.tick().unwrap();
+            let log: Vec<Cycle> = cpu.bus.log.borrow_mut().drain(..).collect();
+            assert_eq!(log.len(), 1, "cycle {}: {:?}", cycles.len() + 1, log);
+            cycles.extend(log);
+            if cpu.at_instruction_boundary() {
+                return cycles;
+            }
+        }
+    }
+
+    #[test]
+    fn read_modify_write_writes_the_old_value_back_first() {
+        let mut cpu = cpu_at(0x0400, "asl $10");
+        cpu.bus.ram.data[0x10] = 0x41;
+        assert_eq!(
+            tick_instruction(&mut cpu),
+            [
+                Read(0x0400),
+                Read(0x0401),
+                Read(0x0010),
+                Write(0x0010, 0x41),
+                Write(0x0010, 0x82),
+            ]
+        );
+    }
+
+    #[test]
+    fn indexed_reads_make_a_dummy_read_only_when_crossing_a_page() {
+        let mut cpu = cpu_at(0x0400, "lda $1080,x\nlda $10ff,x");
+        cpu.x = 1;
+        assert_eq!(
+            tick_instruction(&mut cpu),
+            [Read(0x0400), Read(0x0401), Read(0x0402), Read(0x1081)]
+        );
+        assert_eq!(
+            tick_instruction(&mut cpu),
+            [
+                Read(0x0403),
+                Read(0x0404),
+                Read(0x0405),
+                Read(0x1000),
+                Read(0x1100),
+            ]
+        );
+    }
+
+    #[test]
+    fn indexed_writes_always_make_the_dummy_read() {
+        let mut cpu = cpu_at(0x0400, "sta $1080,x");
+        cpu.x = 1;
+        cpu.a = 0x55;
+        assert_eq!(
+            tick_instruction(&mut cpu),
+            [
+                Read(0x0400),
+                Read(0x0401),
+                Read(0x0402),
+                Read(0x1081),
+                Write(0x1081, 0x55),
+            ]
+        );
+    }
+
+    #[test]
+    fn branches_take_a_cycle_more_when_taken_and_another_across_a_page() {
+        let mut cpu = cpu_at(
+            0x04F0,
+            "bne skip\nbeq skip\nskip: beq far\n.org $0510\nfar:",
+        );
+        cpu.status.zero = true;
+
+        // not taken
+        assert_eq!(tick_instruction(&mut cpu), [Read(0x04F0), Read(0x04F1)]);
+        // taken, the next opcode is read while the offset is added
+        assert_eq!(
+            tick_instruction(&mut cpu),
+            [Read(0x04F2), Read(0x04F3), Read(0x04F4)]
+        );
+        assert_eq!(cpu.pc, 0x04F4);
+        // taken across a page, the address before the carry is read
+        assert_eq!(
+            tick_instruction(&mut cpu),
+            [Read(0x04F4), Read(0x04F5), Read(0x04F6), Read(0x0410)]
+        );
+        assert_eq!(cpu.pc, 0x0510);
+    }
+
+    #[test]
+    fn an_irq_raised_before_the_last_cycle_is_taken_after_the_instruction() {
+        let mut cpu = cpu_at(0x0400, "nop\nnop");
+        cpu.status.disable_interrupts = false;
+
+        cpu.tick().unwrap();
+        cpu.set_irq(true);
+        cpu.tick().unwrap();
+        assert!(cpu.at_instruction_boundary());
+
+        assert_eq!(tick_instruction(&mut cpu).len(), 7);
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+    }
+
+    #[test]
+    fn an_irq_raised_during_the_last_cycle_waits_for_the_next_instruction() {
+        let mut cpu = cpu_at(0x0400, "nop\nnop");
+        cpu.status.disable_interrupts = false;
+
+        tick_instruction(&mut cpu);
+        cpu.set_irq(true);
+        assert_eq!(tick_instruction(&mut cpu), [Read(0x0401), Read(0x0402)]);
+
+        tick_instruction(&mut cpu);
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+    }
+
+    #[test]
+    fn the_instruction_after_cli_runs_before_a_pending_irq() {
+        let mut cpu = cpu_at(0x0400, "cli\nnop\nnop");
+        cpu.set_irq(true);
+
+        tick_instruction(&mut cpu);
+        assert_eq!(tick_instruction(&mut cpu), [Read(0x0401), Read(0x0402)]);
+
+        tick_instruction(&mut cpu);
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+    }
+
+    #[test]
+    fn a_taken_branch_on_the_same_page_delays_an_nmi() {
+        let mut cpu = cpu_at(0x0400, "beq next\nnext: nop");
+        cpu.status.zero = true;
+
+        cpu.tick().unwrap();
+        cpu.tick().unwrap();
+        cpu.set_nmi(true);
+        cpu.tick().unwrap();
+        assert!(cpu.at_instruction_boundary());
+
+        // the NOP runs first
+        assert_eq!(tick_instruction(&mut cpu), [Read(0x0402), Read(0x0403)]);
+        tick_instruction(&mut cpu);
+        assert_eq!(cpu.pc, NMI_HANDLER);
+    }
+
+    #[test]
+    fn the_first_instruction_of_a_handler_runs_before_another_interrupt() {
+        let mut cpu = cpu_at(0x0400, "nop");
+        cpu.status.disable_interrupts = false;
+        cpu.set_irq(true);
+
+        // too late for the NMI to hijack the IRQ
+        for _ in 0..6 {
+            cpu.tick().unwrap();
+        }
+        cpu.set_nmi(true);
+        cpu.tick().unwrap();
+        assert!(cpu.at_instruction_boundary());
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+
+        assert_eq!(
+            tick_instruction(&mut cpu),
+            [Read(IRQ_HANDLER), Read(IRQ_HANDLER + 1)]
+        );
+        tick_instruction(&mut cpu);
+        assert_eq!(cpu.pc, NMI_HANDLER);
+    }
+}
@@ -0,0 +1,111 @@
+use std::{fmt::Display, fmt::Write, path::Path};
+
+use crate::{
+    cpu::{Cpu, IRQ_VECTOR, NMI_VECTOR, RESET_VECTOR, STACK},
+    mem::Mem,
+};
+
+/// A report for bugs that stop the CPU, with the reason, the registers, the
+/// instruction history, the stack page, the zero page and the vectors.
+///
+/// Memory is read through `Mem::peek_byte`, so dumping neither disturbs
+/// the hardware nor records faults for the next `Cpu::step` to report.
+pub fn dump<M: Mem>(cpu: &Cpu<M>, reason: impl Display) -> String {
+    let mut out = String::new();
+    writeln!(out, "reason: {}", reason).unwrap();
+    writeln!(out, "variant: {:?}", cpu.variant).unwrap();
+    writeln!(out, "registers: {}", cpu).unwrap();
+    writeln!(out, "cycles: {}", cpu.cycles).unwrap();
+
+    writeln!(
+        out,
+        "\nhistory, last {} instructions, oldest first:",
+        cpu.history.len()
+    )
+    .unwrap();
+    let instructions = cpu.variant.instructions();
+    for entry in cpu.history.iter() {
+        writeln!(
+            out,
+            "{:04X}  {:02X}  {:4} A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} CYC:{}",
+            entry.pc,
+            entry.opcode,
+            instructions[entry.opcode as usize].mnemonic,
+            entry.a,
+            entry.x,
+            entry.y,
+            entry.status,
+            entry.sp,
+            entry.cycles
+        )
+        .unwrap();
+    }
+
+    writeln!(out, "\nstack:").unwrap();
+    hexdump(&mut out, cpu, STACK);
+    writeln!(out, "\nzero page:").unwrap();
+    hexdump(&mut out, cpu, 0x0000);
+
+    writeln!(out, "\nvectors:").unwrap();
+    for (name, vector) in [
+        ("NMI", NMI_VECTOR),
+        ("RESET", RESET_VECTOR),
+        ("IRQ", IRQ_VECTOR),
+    ] {
+        writeln!(out, "{:5} ${:04X}", name, cpu.bus.peek_word(vector)).unwrap();
+    }
+
+    out
+}
+
+/// Writes `dump` to `path`.
+pub fn write<M: Mem>(cpu: &Cpu<M>, reason: impl Display, path: &Path) -> std::io::Result<()> {
+    std::fs::write(path, dump(cpu, reason))
+}
+
+/// Prints the 256 byte page at `start`, 16 bytes to a row.
+fn hexdump<M: Mem>(out: &mut String, cpu: &Cpu<M>, start: u16) {
+    for row in (start..start + 0x100).step_by(16) {
+        let bytes = (row..row + 16)
+            .map(|addr| format!("{:02X}", cpu.bus.peek_byte(addr)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(out, "{:04X}: {}", row, bytes).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mem::Ram;
+
+    #[test]
+    fn dump_has_the_state_history_and_memory() {
+        let mut ram = Ram::new();
+        // lda #$42, pha, jam
+        ram.load(0x0400, &[0xA9, 0x42, 0x48, 0x02]);
+        ram.load(NMI_VECTOR, &[0x34, 0x12, 0x00, 0x04, 0x78, 0x56]);
+        ram.data[0x0010] = 0x99;
+        let mut cpu = Cpu::new(ram);
+        let reason = loop {
+            if let Some(reason) = cpu.step().unwrap() {
+                break reason;
+            }
+        };
+
+        let dump = dump(&cpu, format!("{:?}", reason));
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(lines[0], "reason: Jammed(Jam { opcode: 2, addr: 1027 })");
+        assert!(lines.contains(&"history, last 3 instructions, oldest first:"));
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("0400  A9  LDA  A:00 X:00 Y:00 P:24 SP:FD CYC:7")));
+        assert!(lines.iter().any(|line| line.starts_with("0403  02")));
+        // the pushed A is at the top of the stack, $10 in the zero page
+        assert!(lines.contains(&"01F0: 00 00 00 00 00 00 00 00 00 00 00 00 00 42 00 00"));
+        assert!(lines.contains(&"0010: 99 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00"));
+        assert!(lines.contains(&"NMI   $1234"));
+        assert!(lines.contains(&"RESET $0400"));
+        assert!(lines.contains(&"IRQ   $5678"));
+    }
+}
@@ -0,0 +1,457 @@
+use std::{collections::BTreeMap, fmt::Write, ops::RangeInclusive};
+
+use crate::{
+    cpu::{AddressingMode, Variant, IRQ_VECTOR, NMI_VECTOR, RESET_VECTOR},
+    instruction::{decode_for, DecodedInstruction},
+    rom::Rom,
+};
+
+/// Disassembles a block of code loaded at `base`, without needing a `Cpu`.
+///
+/// The bytes are decoded in a single linear sweep from the start, so data
+/// in between code has to be listed in `data` to keep it from being decoded
+/// as instructions. Branch, JMP and JSR targets are labelled, but control
+/// flow is not followed.
+#[derive(Debug)]
+pub struct Disassembler<'a> {
+    pub bytes: &'a [u8],
+    pub base: u16,
+    pub variant: Variant,
+    /// Address ranges that hold data rather than code.
+    pub data: Vec<RangeInclusive<u16>>,
+}
+
+/// One line of a disassembly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+    Instruction {
+        addr: u16,
+        instruction: DecodedInstruction,
+    },
+    /// A byte in a data range, or one that does not start a whole instruction.
+    Byte { addr: u16, value: u8 },
+}
+
+impl Line {
+    pub fn addr(&self) -> u16 {
+        match self {
+            Line::Instruction { addr, .. } | Line::Byte { addr, .. } => *addr,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Disassembly {
+    pub variant: Variant,
+    pub lines: Vec<Line>,
+    /// Names for the branch and jump targets that start an instruction.
+    pub labels: BTreeMap<u16, String>,
+}
+
+impl<'a> Disassembler<'a> {
+    pub fn new(bytes: &'a [u8], base: u16) -> Self {
+        Self {
+            bytes,
+            base,
+            variant: Variant::default(),
+            data: Vec::new(),
+        }
+    }
+
+    /// Disassembles the PRG ROM where an NROM cartridge maps it, a 16K ROM at
+    /// $C000 and a 32K one at $8000. Larger ROMs are banked, for those the
+    /// last 32K, which hold the vectors, are used. The vectors are treated as
+    /// data and their targets labelled `nmi`, `reset` and `irq`.
+    pub fn from_rom(rom: &'a Rom) -> Self {
+        let len = rom.prg_rom.len().min(0x8000);
+        let bytes = &rom.prg_rom[rom.prg_rom.len() - len..];
+        let mut disassembler = Self::new(bytes, (0x10000 - len) as u16);
+        if len >= 6 {
+            disassembler.data.push(NMI_VECTOR..=0xFFFF);
+        }
+        disassembler
+    }
+
+    fn is_data(&self, addr: u16) -> bool {
+        self.data.iter().any(|range| range.contains(&addr))
+    }
+
+    fn read_word(&self, addr: u16) -> Option<u16> {
+        let offset = addr.checked_sub(self.base)? as usize;
+        let lo = *self.bytes.get(offset)? as u16;
+        let hi = *self.bytes.get(offset + 1)? as u16;
+        Some(hi << 8 | lo)
+    }
+
+    /// Decodes every byte outside of `data` as an instruction where a whole
+    /// one fits, then labels the targets that start an instruction.
+    pub fn disassemble(&self) -> Disassembly {
+        let mut lines = Vec::new();
+
+        let mut offset = 0;
+        while offset < self.bytes.len() {
+            let addr = self.base.wrapping_add(offset as u16);
+            let instruction = decode_for(self.variant, &self.bytes[offset..])
+                .filter(|ins| (0..ins.length as u16).all(|i| !self.is_data(addr.wrapping_add(i))));
+
+            match instruction {
+                Some(instruction) => {
+                    lines.push(Line::Instruction { addr, instruction });
+                    offset += instruction.length as usize;
+                }
+                None => {
+                    lines.push(Line::Byte {
+                        addr,
+                        value: self.bytes[offset],
+                    });
+                    offset += 1;
+                }
+            }
+        }
+
+        let starts: Vec<u16> = lines
+            .iter()
+            .filter(|line| matches!(line, Line::Instruction { .. }))
+            .map(Line::addr)
+            .collect();
+
+        let mut labels = BTreeMap::new();
+        for (name, vector) in [
+            ("nmi", NMI_VECTOR),
+            ("reset", RESET_VECTOR),
+            ("irq", IRQ_VECTOR),
+        ] {
+            if !self.is_data(vector) {
+                continue;
+            }
+            if let Some(target) = self.read_word(vector) {
+                if starts.contains(&target) {
+                    labels.entry(target).or_insert_with(|| name.to_string());
+                }
+            }
+        }
+        for line in &lines {
+            if let Line::Instruction { addr, instruction } = line {
+                if let Some(target) = code_target(*addr, instruction) {
+                    if starts.contains(&target) {
+                        labels
+                            .entry(target)
+                            .or_insert_with(|| format!("L{:04X}", target));
+                    }
+                }
+            }
+        }
+
+        Disassembly {
+            variant: self.variant,
+            lines,
+            labels,
+        }
+    }
+}
+
+/// The address a branch, JMP or JSR continues at.
+fn code_target(addr: u16, ins: &DecodedInstruction) -> Option<u16> {
+    match ins.mode {
+        AddressingMode::Relative => {
+            let offset = ins.operand as u8 as i8;
+            Some(addr.wrapping_add(2).wrapping_add(offset as u16))
+        }
+        AddressingMode::ZeroPageRelative => {
+            let offset = (ins.operand >> 8) as u8 as i8;
+            Some(addr.wrapping_add(3).wrapping_add(offset as u16))
+        }
+        AddressingMode::Absolute if ins.mnemonic == "JMP" || ins.mnemonic == "JSR" => {
+            Some(ins.operand)
+        }
+        _ => None,
+    }
+}
+
+impl Disassembly {
+    fn address(&self, addr: u16) -> String {
+        match self.labels.get(&addr) {
+            Some(label) => label.clone(),
+            None => format!("${:04X}", addr),
+        }
+    }
+
+    fn operand(&self, addr: u16, ins: &DecodedInstruction, ca65: bool) -> String {
+        let zp = ins.operand as u8;
+        // ca65 picks zero page addressing for small operands unless told otherwise
+        let abs = if ca65 && ins.operand < 0x100 && !matches!(ins.mnemonic, "JMP" | "JSR") {
+            format!("a:{}", self.address(ins.operand))
+        } else {
+            self.address(ins.operand)
+        };
+
+        match ins.mode {
+            AddressingMode::Immediate => format!("#${:02X}", zp),
+            AddressingMode::ZeroPage => format!("${:02X}", zp),
+            AddressingMode::ZeroPageX => format!("${:02X},X", zp),
+            AddressingMode::ZeroPageY => format!("${:02X},Y", zp),
+            AddressingMode::Absolute => abs,
+            AddressingMode::AbsoluteX => format!("{},X", abs),
+            AddressingMode::AbsoluteY => format!("{},Y", abs),
+            AddressingMode::Indirect => format!("({})", self.address(ins.operand)),
+            AddressingMode::IndirectX => format!("(${:02X},X)", zp),
+            AddressingMode::IndirectY => format!("(${:02X}),Y", zp),
+            AddressingMode::ZeroPageIndirect => format!("(${:02X})", zp),
+            AddressingMode::AbsoluteIndexedIndirect => {
+                format!("({},X)", self.address(ins.operand))
+            }
+            AddressingMode::Relative | AddressingMode::ZeroPageRelative => {
+                let target = self.address(code_target(addr, ins).unwrap_or_default());
+                if ins.mode == AddressingMode::Relative {
+                    target
+                } else {
+                    format!("${:02X},{}", zp, target)
+                }
+            }
+            AddressingMode::None => match ins.mnemonic {
+                "ASL" | "LSR" | "ROL" | "ROR" | "INC" | "DEC" if ca65 => "a".to_string(),
+                "ASL" | "LSR" | "ROL" | "ROR" | "INC" | "DEC" => "A".to_string(),
+                _ => String::new(),
+            },
+        }
+    }
+
+    fn bytes(ins: &DecodedInstruction) -> Vec<u8> {
+        let operand = ins.operand.to_le_bytes();
+        let mut bytes = vec![ins.opcode];
+        bytes.extend_from_slice(&operand[..ins.length as usize - 1]);
+        bytes
+    }
+
+    /// A listing with addresses and the bytes of each instruction, in the
+    /// style of `trace::trace`. Unofficial opcodes are marked with a `*`.
+    pub fn listing(&self) -> String {
+        let mut out = String::new();
+        for line in &self.lines {
+            if let Some(label) = self.labels.get(&line.addr()) {
+                writeln!(out, "{}:", label).unwrap();
+            }
+
+            match line {
+                Line::Instruction { addr, instruction } => {
+                    let hex = Self::bytes(instruction)
+                        .iter()
+                        .map(|b| format!("{:02X}", b))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let mnemonic = if instruction.official {
+                        format!(" {}", instruction.mnemonic)
+                    } else {
+                        format!("*{}", instruction.mnemonic)
+                    };
+                    let text = format!(
+                        "{:04X}  {:8} {} {}",
+                        addr,
+                        hex,
+                        mnemonic,
+                        self.operand(*addr, instruction, false)
+                    );
+                    writeln!(out, "{}", text.trim_end()).unwrap();
+                }
+                Line::Byte { addr, value } => {
+                    writeln!(
+                        out,
+                        "{:04X}  {:02X}        .byte ${:02X}",
+                        addr, value, value
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        out
+    }
+
+    /// Source that ca65 assembles back into the same bytes. Unofficial
+    /// opcodes are written as `.byte` with the instruction in a comment.
+    /// ca65's plain 65C02 lacks the WDC bit instructions, WAI and STP, so
+    /// the W65C02 is selected when any of them is used.
+    pub fn ca65(&self) -> String {
+        let wdc = self.lines.iter().any(|line| match line {
+            Line::Instruction { instruction, .. } => {
+                matches!(instruction.mnemonic, "WAI" | "STP")
+                    || ["RMB", "SMB", "BBR", "BBS"]
+                        .iter()
+                        .any(|prefix| instruction.mnemonic.starts_with(prefix))
+            }
+            Line::Byte { .. } => false,
+        });
+        let cpu = match (self.variant.is_cmos(), wdc) {
+            (false, _) => "6502",
+            (true, false) => "65C02",
+            (true, true) => "W65C02",
+        };
+
+        let mut out = String::new();
+        writeln!(out, ".setcpu \"{}\"", cpu).unwrap();
+        if let Some(line) = self.lines.first() {
+            writeln!(out, ".org ${:04X}", line.addr()).unwrap();
+        }
+        writeln!(out).unwrap();
+
+        for line in &self.lines {
+            if let Some(label) = self.labels.get(&line.addr()) {
+                writeln!(out, "{}:", label).unwrap();
+            }
+
+            match line {
+                Line::Instruction { addr, instruction } if instruction.official => {
+                    let text = format!(
+                        "        {} {}",
+                        instruction.mnemonic.to_ascii_lowercase(),
+                        self.operand(*addr, instruction, true)
+                    );
+                    writeln!(out, "{}", text.trim_end()).unwrap();
+                }
+                Line::Instruction { addr, instruction } => {
+                    let bytes = Self::bytes(instruction)
+                        .iter()
+                        .map(|b| format!("${:02X}", b))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let text = format!(
+                        "{} {}",
+                        instruction.mnemonic,
+                        self.operand(*addr, instruction, false)
+                    );
+                    writeln!(out, "        .byte {} ; {}", bytes, text.trim_end()).unwrap();
+                }
+                Line::Byte { value, .. } => writeln!(out, "        .byte ${:02X}", value).unwrap(),
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{asm::assemble, rom::Mirroring};
+
+    const PROGRAM: [u8; 29] = [
+        0xA9, 0x10, // 8000 LDA #$10
+        0x85, 0x20, // 8002 STA $20
+        0xAD, 0x20, 0x00, // 8004 LDA $0020
+        0xBD, 0x00, 0x02, // 8007 LDA $0200,X
+        0x0A, // 800A ASL A
+        0xD0, 0xF3, // 800B BNE $8000
+        0x20, 0x15, 0x80, // 800D JSR $8015
+        0x4C, 0x0B, 0x80, // 8010 JMP $800B
+        0x07, 0x20, // 8013 SLO $20
+        0x6C, 0x00, 0x03, // 8015 JMP ($0300)
+        0xB1, 0x20, // 8018 LDA ($20),Y
+        0x60, // 801A RTS
+        0xAD, 0x34, // 801B, cut off
+    ];
+
+    #[test]
+    fn ca65_output_reassembles_to_the_same_bytes() {
+        let source = Disassembler::new(&PROGRAM, 0x8000).disassemble().ca65();
+        assert!(source.contains("lda a:$0020"), "{}", source);
+
+        // the assembler only knows the 6502, which is what .setcpu selects
+        let source: String = source
+            .lines()
+            .filter(|line| !line.starts_with(".setcpu"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        let program = assemble(&source, 0).unwrap();
+        assert_eq!(program.origin, 0x8000);
+        assert_eq!(program.bytes, PROGRAM);
+    }
+
+    #[test]
+    fn branch_and_jump_targets_are_labelled() {
+        let disassembly = Disassembler::new(&PROGRAM, 0x8000).disassemble();
+        let labels: Vec<_> = disassembly
+            .labels
+            .iter()
+            .map(|(&addr, label)| (addr, label.as_str()))
+            .collect();
+        assert_eq!(
+            labels,
+            [(0x8000, "L8000"), (0x800B, "L800B"), (0x8015, "L8015")]
+        );
+
+        let listing = disassembly.listing();
+        assert!(listing.contains("L800B:\n800B  D0 F3     BNE L8000\n"));
+        assert!(listing.contains("800D  20 15 80  JSR L8015\n"));
+        // an indirect jump's target is not known
+        assert!(listing.contains("8015  6C 00 03  JMP ($0300)\n"));
+    }
+
+    #[test]
+    fn data_is_emitted_as_bytes() {
+        let mut disassembler = Disassembler::new(&PROGRAM, 0x8000);
+        let listing = disassembler.disassemble().listing();
+        assert!(listing.contains("8013  07 20    *SLO $20\n"));
+        assert!(listing.ends_with("801B  AD        .byte $AD\n801C  34        .byte $34\n"));
+        let ca65 = disassembler.disassemble().ca65();
+        assert!(ca65.contains("        .byte $07, $20 ; SLO $20\n"));
+
+        disassembler.data.push(0x8013..=0x8014);
+        let disassembly = disassembler.disassemble();
+        assert_eq!(
+            disassembly.lines[8..10],
+            [
+                Line::Byte {
+                    addr: 0x8013,
+                    value: 0x07
+                },
+                Line::Byte {
+                    addr: 0x8014,
+                    value: 0x20
+                },
+            ]
+        );
+        assert_eq!(disassembly.lines[10].addr(), 0x8015);
+    }
+
+    #[test]
+    fn vector_targets_of_a_rom_are_named() {
+        let mut prg_rom = vec![0xEA; 0x4000];
+        prg_rom[0x3FFA..].copy_from_slice(&[0x02, 0xC0, 0x00, 0xC0, 0x04, 0xC0]);
+        let rom = Rom {
+            prg_rom,
+            chr_rom: Vec::new(),
+            mapper: 0,
+            submapper: 0,
+            mirroring: Mirroring::Horizontal,
+            prg_ram_size: 0,
+        };
+
+        let disassembly = Disassembler::from_rom(&rom).disassemble();
+        assert_eq!(disassembly.lines[0].addr(), 0xC000);
+        assert_eq!(disassembly.labels[&0xC000], "reset");
+        assert_eq!(disassembly.labels[&0xC002], "nmi");
+        assert_eq!(disassembly.labels[&0xC004], "irq");
+        assert_eq!(
+            disassembly.lines.last(),
+            Some(&Line::Byte {
+                addr: 0xFFFF,
+                value: 0xC0
+            })
+        );
+    }
+
+    #[test]
+    fn ca65_selects_the_w65c02_for_its_extra_instructions() {
+        let ca65 = |bytes: &[u8]| {
+            let mut disassembler = Disassembler::new(bytes, 0x8000);
+            disassembler.variant = Variant::Wdc65C02;
+            disassembler.disassemble().ca65()
+        };
+
+        // STZ $10
+        assert!(ca65(&[0x64, 0x10]).starts_with(".setcpu \"65C02\"\n"));
+        // RMB0 $10, BBS7 $10,*, WAI, STP
+        for bytes in [&[0x07, 0x10][..], &[0xFF, 0x10, 0xFD], &[0xCB], &[0xDB]] {
+            let source = ca65(bytes);
+            assert!(source.starts_with(".setcpu \"W65C02\"\n"), "{}", source);
+        }
+    }
+}
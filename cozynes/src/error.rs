@@ -0,0 +1,144 @@
+use crate::cpu::AddressingMode;
+
+/// Faults the emulator core can run into while executing or tracing code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// An operand address was requested for an addressing mode that has none.
+    InvalidAddressingMode {
+        mode: AddressingMode,
+        addr: u16,
+    },
+    /// An instruction was traced with an addressing mode that does not fit
+    /// its length.
+    UnexpectedTraceMode {
+        opcode: u8,
+        mode: AddressingMode,
+    },
+    /// A write to cartridge ROM.
+    RomWrite {
+        addr: u16,
+        value: u8,
+    },
+    /// An access to hardware that is not emulated yet, such as the PPU.
+    UnimplementedHardware {
+        addr: u16,
+    },
+    UnmappedRead {
+        addr: u16,
+    },
+    UnmappedWrite {
+        addr: u16,
+        value: u8,
+    },
+}
+
+/// The class of an `Error`, used to pick the `Policy` applied to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultKind {
+    InvalidAddressingMode,
+    RomWrite,
+    UnimplementedHardware,
+    UnmappedAccess,
+}
+
+/// What to do when a fault is encountered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Carry on as if nothing happened.
+    Ignore,
+    /// Log a warning and carry on.
+    Log,
+    /// Stop and return the fault as an `Err`.
+    Halt,
+}
+
+/// The `Policy` for each `FaultKind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaultPolicy {
+    pub invalid_addressing_mode: Policy,
+    pub rom_write: Policy,
+    pub unimplemented_hardware: Policy,
+    pub unmapped_access: Policy,
+}
+
+impl Default for FaultPolicy {
+    fn default() -> Self {
+        Self {
+            invalid_addressing_mode: Policy::Halt,
+            rom_write: Policy::Halt,
+            unimplemented_hardware: Policy::Log,
+            unmapped_access: Policy::Ignore,
+        }
+    }
+}
+
+impl FaultPolicy {
+    /// Applies the same policy to every kind of fault.
+    pub fn all(policy: Policy) -> Self {
+        Self {
+            invalid_addressing_mode: policy,
+            rom_write: policy,
+            unimplemented_hardware: policy,
+            unmapped_access: policy,
+        }
+    }
+
+    pub fn get(&self, kind: FaultKind) -> Policy {
+        match kind {
+            FaultKind::InvalidAddressingMode => self.invalid_addressing_mode,
+            FaultKind::RomWrite => self.rom_write,
+            FaultKind::UnimplementedHardware => self.unimplemented_hardware,
+            FaultKind::UnmappedAccess => self.unmapped_access,
+        }
+    }
+}
+
+impl Error {
+    pub fn kind(&self) -> FaultKind {
+        match self {
+            Error::InvalidAddressingMode { .. } | Error::UnexpectedTraceMode { .. } => {
+                FaultKind::InvalidAddressingMode
+            }
+            Error::RomWrite { .. } => FaultKind::RomWrite,
+            Error::UnimplementedHardware { .. } => FaultKind::UnimplementedHardware,
+            Error::UnmappedRead { .. } | Error::UnmappedWrite { .. } => FaultKind::UnmappedAccess,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidAddressingMode { mode, addr } => {
+                write!(
+                    f,
+                    "addressing mode {:?} has no operand at {:#06x}",
+                    mode, addr
+                )
+            }
+            Error::UnexpectedTraceMode { opcode, mode } => {
+                write!(
+                    f,
+                    "unexpected addressing mode {:?} for opcode {:#04x}",
+                    mode, opcode
+                )
+            }
+            Error::RomWrite { addr, value } => {
+                write!(
+                    f,
+                    "write of {:#04x} to cartridge rom address {:#06x}",
+                    value, addr
+                )
+            }
+            Error::UnimplementedHardware { addr } => {
+                write!(f, "access to unimplemented hardware at {:#06x}", addr)
+            }
+            Error::UnmappedRead { addr } => write!(f, "unmapped read at {:#06x}", addr),
+            Error::UnmappedWrite { addr, value } => {
+                write!(f, "unmapped write of {:#04x} at {:#06x}", value, addr)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
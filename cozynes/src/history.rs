@@ -0,0 +1,113 @@
+use std::collections::VecDeque;
+
+/// How many instructions `History::default` keeps.
+pub const HISTORY_LEN: usize = 64;
+
+/// The CPU state right before an instruction was executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    pub pc: u16,
+    pub opcode: u8,
+    pub a: u8,
+    pub x: u8,
+    pub y: u8,
+    pub sp: u8,
+    pub status: u8,
+    pub cycles: usize,
+}
+
+/// A ring buffer of the most recently executed instructions.
+#[derive(Debug, Clone)]
+pub struct History {
+    entries: VecDeque<Entry>,
+    capacity: usize,
+}
+
+impl History {
+    /// Keeps the last `capacity` instructions, 0 disables recording.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn record(&mut self, entry: Entry) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// The recorded instructions, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(HISTORY_LEN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pc: u16) -> Entry {
+        Entry {
+            pc,
+            opcode: 0xEA,
+            a: 0,
+            x: 0,
+            y: 0,
+            sp: 0xFD,
+            status: 0x24,
+            cycles: 0,
+        }
+    }
+
+    #[test]
+    fn the_oldest_entries_are_evicted_once_full() {
+        let mut history = History::new(3);
+        for pc in 0..5 {
+            history.record(entry(pc));
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(
+            history.iter().map(|entry| entry.pc).collect::<Vec<_>>(),
+            [2, 3, 4]
+        );
+
+        history.clear();
+        assert!(history.is_empty());
+        assert_eq!(history.capacity(), 3);
+    }
+
+    #[test]
+    fn a_capacity_of_zero_records_nothing() {
+        let mut history = History::new(0);
+        history.record(entry(0));
+        assert!(history.is_empty());
+    }
+}
@@ -1,19 +1,34 @@
-use super::cpu::AddressingMode;
+use std::ops::BitOr;
 
+use super::cpu::{AddressingMode, Variant};
+
+/// An entry of an opcode table.
 #[derive(Debug)]
-pub(crate) struct Instruction {
-    #[allow(unused)]
+pub struct Instruction {
     pub opcode: u8,
-    #[allow(unused)]
     pub mnemonic: &'static str,
+    /// Base cycle count, without page crossing and branch penalties.
     pub cycles: usize,
     pub bytes: Size,
     pub mode: AddressingMode,
+    pub access: Access,
+    /// False for the undocumented opcodes.
+    pub official: bool,
+}
+
+/// How an instruction accesses its memory operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// The instruction has no memory operand, or only uses it as a jump target.
+    None,
+    Read,
+    Write,
+    ReadModifyWrite,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
-pub(crate) enum Size {
+pub enum Size {
     One = 1,
     Two = 2,
     Three = 3,
@@ -29,13 +44,137 @@ impl std::fmt::Debug for Size {
     }
 }
 
-pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
+/// A set of status flags, using the bit positions of the status register.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Flags(pub u8);
+
+impl Flags {
+    pub const NONE: Flags = Flags(0);
+    pub const CARRY: Flags = Flags(0b0000_0001);
+    pub const ZERO: Flags = Flags(0b0000_0010);
+    pub const INTERRUPT: Flags = Flags(0b0000_0100);
+    pub const DECIMAL: Flags = Flags(0b0000_1000);
+    pub const OVERFLOW: Flags = Flags(0b0100_0000);
+    pub const NEGATIVE: Flags = Flags(0b1000_0000);
+    pub const ALL: Flags = Flags(0b1100_1111);
+
+    pub fn contains(self, other: Flags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for Flags {
+    type Output = Flags;
+
+    fn bitor(self, rhs: Flags) -> Flags {
+        Flags(self.0 | rhs.0)
+    }
+}
+
+impl Instruction {
+    /// The status flags the instruction's result depends on.
+    pub fn flags_read(&self) -> Flags {
+        match self.mnemonic {
+            "ADC" | "SBC" | "RRA" | "ISB" | "ARR" => Flags::CARRY | Flags::DECIMAL,
+            "ROL" | "ROR" | "RLA" => Flags::CARRY,
+            "BCC" | "BCS" => Flags::CARRY,
+            "BEQ" | "BNE" => Flags::ZERO,
+            "BMI" | "BPL" => Flags::NEGATIVE,
+            "BVC" | "BVS" => Flags::OVERFLOW,
+            "PHP" | "BRK" => Flags::ALL,
+            _ => Flags::NONE,
+        }
+    }
+
+    /// The status flags the instruction may change when run by `variant`.
+    pub fn flags_written(&self, variant: Variant) -> Flags {
+        let nz = Flags::NEGATIVE | Flags::ZERO;
+        match self.mnemonic {
+            "ADC" | "SBC" | "RRA" | "ISB" | "ARR" => nz | Flags::CARRY | Flags::OVERFLOW,
+            "ASL" | "LSR" | "ROL" | "ROR" | "SLO" | "SRE" | "RLA" => nz | Flags::CARRY,
+            "CMP" | "CPX" | "CPY" | "DCP" | "AXS" | "ANC" | "ALR" => nz | Flags::CARRY,
+            "AND" | "ORA" | "EOR" | "LDA" | "LDX" | "LDY" | "LAX" | "LAS" | "XAA" | "TAX"
+            | "TAY" | "TXA" | "TYA" | "TSX" | "INX" | "INY" | "DEX" | "DEY" | "INC" | "DEC"
+            | "PLA" | "PLX" | "PLY" => nz,
+            // BIT #imm on the 65C02 only affects Z
+            "BIT" if self.mode == AddressingMode::Immediate => Flags::ZERO,
+            "BIT" => nz | Flags::OVERFLOW,
+            "TSB" | "TRB" => Flags::ZERO,
+            "CLC" | "SEC" => Flags::CARRY,
+            "CLI" | "SEI" => Flags::INTERRUPT,
+            "CLD" | "SED" => Flags::DECIMAL,
+            "CLV" => Flags::OVERFLOW,
+            "PLP" | "RTI" => Flags::ALL,
+            // only the 65C02 clears D when taking an interrupt
+            "BRK" if variant.is_cmos() => Flags::INTERRUPT | Flags::DECIMAL,
+            "BRK" => Flags::INTERRUPT,
+            _ => Flags::NONE,
+        }
+    }
+}
+
+/// An instruction decoded from a byte slice, see `decode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodedInstruction {
+    pub opcode: u8,
+    pub mnemonic: &'static str,
+    pub mode: AddressingMode,
+    /// Length in bytes, including the opcode.
+    pub length: u8,
+    /// Base cycle count, without page crossing and branch penalties.
+    pub cycles: usize,
+    pub official: bool,
+    pub flags_read: Flags,
+    pub flags_written: Flags,
+    pub access: Access,
+    /// The operand bytes following the opcode, little endian. Zero for one
+    /// byte instructions.
+    pub operand: u16,
+}
+
+/// Decodes the instruction at the start of `bytes` with the NMOS opcode
+/// table. Returns `None` if `bytes` is shorter than the instruction.
+pub fn decode(bytes: &[u8]) -> Option<DecodedInstruction> {
+    decode_for(Variant::default(), bytes)
+}
+
+/// Like `decode`, but with the opcode table of the given CPU variant.
+pub fn decode_for(variant: Variant, bytes: &[u8]) -> Option<DecodedInstruction> {
+    let ins = &variant.instructions()[*bytes.first()? as usize];
+    let length = ins.bytes as u8;
+    let operand = bytes.get(1..length as usize)?;
+    let operand = operand
+        .iter()
+        .rev()
+        .fold(0, |operand, &byte| operand << 8 | byte as u16);
+
+    Some(DecodedInstruction {
+        opcode: ins.opcode,
+        mnemonic: ins.mnemonic,
+        mode: ins.mode,
+        length,
+        cycles: ins.cycles,
+        official: ins.official,
+        flags_read: ins.flags_read(),
+        flags_written: ins.flags_written(variant),
+        access: ins.access,
+        operand,
+    })
+}
+
+pub static INSTRUCTIONS: [Instruction; 256] = [
     Instruction {
         opcode: 0x00,
         mnemonic: "BRK",
         cycles: 7,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x01,
@@ -43,6 +182,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x02,
@@ -50,6 +191,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 0,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0x03,
@@ -57,6 +200,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 8,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x04,
@@ -64,6 +209,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x05,
@@ -71,6 +218,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x06,
@@ -78,6 +227,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x07,
@@ -85,6 +236,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x08,
@@ -92,6 +245,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x09,
@@ -99,6 +254,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x0A,
@@ -106,6 +263,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x0B,
@@ -113,6 +272,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x0C,
@@ -120,6 +281,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x0D,
@@ -127,6 +290,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x0E,
@@ -134,6 +299,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x0F,
@@ -141,6 +308,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x10,
@@ -148,6 +317,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x11,
@@ -155,6 +326,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x12,
@@ -162,6 +335,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 0,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0x13,
@@ -169,6 +344,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 8,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x14,
@@ -176,6 +353,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x15,
@@ -183,6 +362,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x16,
@@ -190,6 +371,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x17,
@@ -197,6 +380,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x18,
@@ -204,6 +389,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x19,
@@ -211,6 +398,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x1A,
@@ -218,6 +407,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0x1B,
@@ -225,6 +416,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x1C,
@@ -232,6 +425,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x1D,
@@ -239,6 +434,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x1E,
@@ -246,6 +443,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x1F,
@@ -253,6 +452,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x20,
@@ -260,6 +461,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x21,
@@ -267,6 +470,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x22,
@@ -274,6 +479,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 0,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0x23,
@@ -281,6 +488,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 8,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x24,
@@ -288,6 +497,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x25,
@@ -295,6 +506,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x26,
@@ -302,6 +515,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x27,
@@ -309,6 +524,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x28,
@@ -316,6 +533,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x29,
@@ -323,6 +542,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x2A,
@@ -330,6 +551,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x2B,
@@ -337,6 +560,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x2C,
@@ -344,6 +569,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x2D,
@@ -351,6 +578,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x2E,
@@ -358,6 +587,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x2F,
@@ -365,6 +596,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x30,
@@ -372,6 +605,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x31,
@@ -379,6 +614,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x32,
@@ -386,6 +623,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 0,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0x33,
@@ -393,6 +632,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 8,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x34,
@@ -400,6 +641,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x35,
@@ -407,6 +650,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x36,
@@ -414,6 +659,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x37,
@@ -421,6 +668,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x38,
@@ -428,6 +677,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x39,
@@ -435,6 +686,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x3A,
@@ -442,6 +695,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0x3B,
@@ -449,6 +704,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x3C,
@@ -456,6 +713,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x3D,
@@ -463,6 +722,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x3E,
@@ -470,6 +731,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x3F,
@@ -477,6 +740,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x40,
@@ -484,6 +749,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x41,
@@ -491,6 +758,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x42,
@@ -498,6 +767,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 0,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0x43,
@@ -505,6 +776,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 8,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x44,
@@ -512,6 +785,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x45,
@@ -519,6 +794,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x46,
@@ -526,6 +803,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x47,
@@ -533,6 +812,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x48,
@@ -540,6 +821,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x49,
@@ -547,6 +830,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x4A,
@@ -554,6 +839,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x4B,
@@ -561,6 +848,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x4C,
@@ -568,6 +857,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x4D,
@@ -575,6 +866,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x4E,
@@ -582,6 +875,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x4F,
@@ -589,6 +884,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x50,
@@ -596,6 +893,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x51,
@@ -603,6 +902,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x52,
@@ -610,6 +911,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 0,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0x53,
@@ -617,6 +920,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 8,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x54,
@@ -624,6 +929,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x55,
@@ -631,6 +938,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x56,
@@ -638,6 +947,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x57,
@@ -645,6 +956,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x58,
@@ -652,6 +965,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x59,
@@ -659,6 +974,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x5A,
@@ -666,6 +983,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0x5B,
@@ -673,6 +992,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x5C,
@@ -680,6 +1001,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x5D,
@@ -687,6 +1010,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x5E,
@@ -694,6 +1019,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x5F,
@@ -701,6 +1028,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x60,
@@ -708,6 +1037,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x61,
@@ -715,6 +1046,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x62,
@@ -722,6 +1055,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 0,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0x63,
@@ -729,6 +1064,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 8,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x64,
@@ -736,6 +1073,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x65,
@@ -743,6 +1082,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x66,
@@ -750,6 +1091,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x67,
@@ -757,6 +1100,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x68,
@@ -764,6 +1109,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x69,
@@ -771,6 +1118,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x6A,
@@ -778,6 +1127,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x6B,
@@ -785,6 +1136,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x6C,
@@ -792,6 +1145,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Three,
         mode: AddressingMode::Indirect,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x6D,
@@ -799,6 +1154,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x6E,
@@ -806,6 +1163,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x6F,
@@ -813,6 +1172,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x70,
@@ -820,6 +1181,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x71,
@@ -827,6 +1190,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x72,
@@ -834,6 +1199,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 0,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0x73,
@@ -841,6 +1208,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 8,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x74,
@@ -848,6 +1217,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x75,
@@ -855,6 +1226,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x76,
@@ -862,6 +1235,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x77,
@@ -869,6 +1244,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x78,
@@ -876,6 +1253,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x79,
@@ -883,6 +1262,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x7A,
@@ -890,6 +1271,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0x7B,
@@ -897,6 +1280,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x7C,
@@ -904,6 +1289,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x7D,
@@ -911,6 +1298,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0x7E,
@@ -918,6 +1307,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0x7F,
@@ -925,6 +1316,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0x80,
@@ -932,6 +1325,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x81,
@@ -939,6 +1334,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::Write,
+        official: true,
     },
     Instruction {
         opcode: 0x82,
@@ -946,6 +1343,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x83,
@@ -953,6 +1352,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::Write,
+        official: false,
     },
     Instruction {
         opcode: 0x84,
@@ -960,6 +1361,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Write,
+        official: true,
     },
     Instruction {
         opcode: 0x85,
@@ -967,6 +1370,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Write,
+        official: true,
     },
     Instruction {
         opcode: 0x86,
@@ -974,6 +1379,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Write,
+        official: true,
     },
     Instruction {
         opcode: 0x87,
@@ -981,6 +1388,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Write,
+        official: false,
     },
     Instruction {
         opcode: 0x88,
@@ -988,6 +1397,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x89,
@@ -995,6 +1406,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x8A,
@@ -1002,6 +1415,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x8B,
@@ -1009,6 +1424,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0x8C,
@@ -1016,6 +1433,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Write,
+        official: true,
     },
     Instruction {
         opcode: 0x8D,
@@ -1023,6 +1442,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Write,
+        official: true,
     },
     Instruction {
         opcode: 0x8E,
@@ -1030,6 +1451,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Write,
+        official: true,
     },
     Instruction {
         opcode: 0x8F,
@@ -1037,6 +1460,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Write,
+        official: false,
     },
     Instruction {
         opcode: 0x90,
@@ -1044,6 +1469,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x91,
@@ -1051,6 +1478,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::Write,
+        official: true,
     },
     Instruction {
         opcode: 0x92,
@@ -1058,6 +1487,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 0,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0x93,
@@ -1065,6 +1496,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::Write,
+        official: false,
     },
     Instruction {
         opcode: 0x94,
@@ -1072,6 +1505,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Write,
+        official: true,
     },
     Instruction {
         opcode: 0x95,
@@ -1079,6 +1514,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Write,
+        official: true,
     },
     Instruction {
         opcode: 0x96,
@@ -1086,6 +1523,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageY,
+        access: Access::Write,
+        official: true,
     },
     Instruction {
         opcode: 0x97,
@@ -1093,6 +1532,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageY,
+        access: Access::Write,
+        official: false,
     },
     Instruction {
         opcode: 0x98,
@@ -1100,6 +1541,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x99,
@@ -1107,6 +1550,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Write,
+        official: true,
     },
     Instruction {
         opcode: 0x9A,
@@ -1114,6 +1559,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0x9B,
@@ -1121,6 +1568,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Write,
+        official: false,
     },
     Instruction {
         opcode: 0x9C,
@@ -1128,6 +1577,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Write,
+        official: false,
     },
     Instruction {
         opcode: 0x9D,
@@ -1135,6 +1586,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Write,
+        official: true,
     },
     Instruction {
         opcode: 0x9E,
@@ -1142,6 +1595,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Write,
+        official: false,
     },
     Instruction {
         opcode: 0x9F,
@@ -1149,6 +1604,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Write,
+        official: false,
     },
     Instruction {
         opcode: 0xA0,
@@ -1156,6 +1613,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xA1,
@@ -1163,6 +1622,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xA2,
@@ -1170,6 +1631,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xA3,
@@ -1177,6 +1640,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xA4,
@@ -1184,6 +1649,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xA5,
@@ -1191,6 +1658,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xA6,
@@ -1198,6 +1667,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xA7,
@@ -1205,6 +1676,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xA8,
@@ -1212,6 +1685,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0xA9,
@@ -1219,6 +1694,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xAA,
@@ -1226,6 +1703,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0xAB,
@@ -1233,6 +1712,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xAC,
@@ -1240,6 +1721,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xAD,
@@ -1247,6 +1730,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xAE,
@@ -1254,6 +1739,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xAF,
@@ -1261,6 +1748,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xB0,
@@ -1268,6 +1757,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0xB1,
@@ -1275,6 +1766,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xB2,
@@ -1282,6 +1775,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 0,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0xB3,
@@ -1289,6 +1784,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xB4,
@@ -1296,6 +1793,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xB5,
@@ -1303,6 +1802,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xB6,
@@ -1310,6 +1811,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xB7,
@@ -1317,6 +1820,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageY,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xB8,
@@ -1324,6 +1829,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0xB9,
@@ -1331,6 +1838,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xBA,
@@ -1338,6 +1847,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0xBB,
@@ -1345,6 +1856,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xBC,
@@ -1352,6 +1865,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xBD,
@@ -1359,6 +1874,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xBE,
@@ -1366,6 +1883,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xBF,
@@ -1373,6 +1892,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xC0,
@@ -1380,6 +1901,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xC1,
@@ -1387,6 +1910,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xC2,
@@ -1394,6 +1919,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xC3,
@@ -1401,6 +1928,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 8,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0xC4,
@@ -1408,6 +1937,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xC5,
@@ -1415,6 +1946,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xC6,
@@ -1422,6 +1955,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0xC7,
@@ -1429,6 +1964,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0xC8,
@@ -1436,6 +1973,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0xC9,
@@ -1443,6 +1982,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xCA,
@@ -1450,6 +1991,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0xCB,
@@ -1457,6 +2000,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xCC,
@@ -1464,6 +2009,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xCD,
@@ -1471,6 +2018,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xCE,
@@ -1478,6 +2027,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0xCF,
@@ -1485,6 +2036,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0xD0,
@@ -1492,6 +2045,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0xD1,
@@ -1499,6 +2054,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xD2,
@@ -1506,6 +2063,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 0,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0xD3,
@@ -1513,6 +2072,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 8,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0xD4,
@@ -1520,6 +2081,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xD5,
@@ -1527,6 +2090,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xD6,
@@ -1534,6 +2099,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0xD7,
@@ -1541,6 +2108,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0xD8,
@@ -1548,6 +2117,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0xD9,
@@ -1555,6 +2126,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xDA,
@@ -1562,6 +2135,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0xDB,
@@ -1569,6 +2144,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0xDC,
@@ -1576,6 +2153,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xDD,
@@ -1583,6 +2162,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xDE,
@@ -1590,6 +2171,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0xDF,
@@ -1597,6 +2180,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0xE0,
@@ -1604,6 +2189,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xE1,
@@ -1611,6 +2198,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xE2,
@@ -1618,6 +2207,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xE3,
@@ -1625,6 +2216,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 8,
         bytes: Size::Two,
         mode: AddressingMode::IndirectX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0xE4,
@@ -1632,6 +2225,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xE5,
@@ -1639,6 +2234,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 3,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xE6,
@@ -1646,6 +2243,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0xE7,
@@ -1653,6 +2252,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0xE8,
@@ -1660,6 +2261,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0xE9,
@@ -1667,6 +2270,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xEA,
@@ -1674,6 +2279,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0xEB,
@@ -1681,6 +2288,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xEC,
@@ -1688,6 +2297,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xED,
@@ -1695,6 +2306,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xEE,
@@ -1702,6 +2315,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0xEF,
@@ -1709,6 +2324,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Three,
         mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0xF0,
@@ -1716,6 +2333,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::Two,
         mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0xF1,
@@ -1723,6 +2342,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 5,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xF2,
@@ -1730,6 +2351,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 0,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0xF3,
@@ -1737,6 +2360,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 8,
         bytes: Size::Two,
         mode: AddressingMode::IndirectY,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0xF4,
@@ -1744,6 +2369,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xF5,
@@ -1751,6 +2378,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xF6,
@@ -1758,6 +2387,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0xF7,
@@ -1765,6 +2396,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 6,
         bytes: Size::Two,
         mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0xF8,
@@ -1772,6 +2405,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
     },
     Instruction {
         opcode: 0xF9,
@@ -1779,6 +2414,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xFA,
@@ -1786,6 +2423,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 2,
         bytes: Size::One,
         mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
     },
     Instruction {
         opcode: 0xFB,
@@ -1793,6 +2432,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteY,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
     Instruction {
         opcode: 0xFC,
@@ -1800,6 +2441,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: false,
     },
     Instruction {
         opcode: 0xFD,
@@ -1807,6 +2450,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 4,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
     },
     Instruction {
         opcode: 0xFE,
@@ -1814,6 +2459,8 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: true,
     },
     Instruction {
         opcode: 0xFF,
@@ -1821,5 +2468,2387 @@ pub(crate) static INSTRUCTIONS: [Instruction; 256] = [
         cycles: 7,
         bytes: Size::Three,
         mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: false,
     },
 ];
+
+/// The WDC 65C02 opcode table, including the Rockwell bit instructions and
+/// WAI/STP. Opcodes that are undefined on the 65C02 are listed as NOPs of the
+/// size and duration they have on real hardware.
+pub static CMOS_INSTRUCTIONS: [Instruction; 256] = [
+    Instruction {
+        opcode: 0x00,
+        mnemonic: "BRK",
+        cycles: 7,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x01,
+        mnemonic: "ORA",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x02,
+        mnemonic: "NOP",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x03,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x04,
+        mnemonic: "TSB",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x05,
+        mnemonic: "ORA",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x06,
+        mnemonic: "ASL",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x07,
+        mnemonic: "RMB0",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x08,
+        mnemonic: "PHP",
+        cycles: 3,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x09,
+        mnemonic: "ORA",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x0A,
+        mnemonic: "ASL",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x0B,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x0C,
+        mnemonic: "TSB",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x0D,
+        mnemonic: "ORA",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x0E,
+        mnemonic: "ASL",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x0F,
+        mnemonic: "BBR0",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x10,
+        mnemonic: "BPL",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x11,
+        mnemonic: "ORA",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x12,
+        mnemonic: "ORA",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageIndirect,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x13,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x14,
+        mnemonic: "TRB",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x15,
+        mnemonic: "ORA",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x16,
+        mnemonic: "ASL",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x17,
+        mnemonic: "RMB1",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x18,
+        mnemonic: "CLC",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x19,
+        mnemonic: "ORA",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x1A,
+        mnemonic: "INC",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x1B,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x1C,
+        mnemonic: "TRB",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x1D,
+        mnemonic: "ORA",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x1E,
+        mnemonic: "ASL",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x1F,
+        mnemonic: "BBR1",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x20,
+        mnemonic: "JSR",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x21,
+        mnemonic: "AND",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x22,
+        mnemonic: "NOP",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x23,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x24,
+        mnemonic: "BIT",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x25,
+        mnemonic: "AND",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x26,
+        mnemonic: "ROL",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x27,
+        mnemonic: "RMB2",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x28,
+        mnemonic: "PLP",
+        cycles: 4,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x29,
+        mnemonic: "AND",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x2A,
+        mnemonic: "ROL",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x2B,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x2C,
+        mnemonic: "BIT",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x2D,
+        mnemonic: "AND",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x2E,
+        mnemonic: "ROL",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x2F,
+        mnemonic: "BBR2",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x30,
+        mnemonic: "BMI",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x31,
+        mnemonic: "AND",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x32,
+        mnemonic: "AND",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageIndirect,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x33,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x34,
+        mnemonic: "BIT",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x35,
+        mnemonic: "AND",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x36,
+        mnemonic: "ROL",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x37,
+        mnemonic: "RMB3",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x38,
+        mnemonic: "SEC",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x39,
+        mnemonic: "AND",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x3A,
+        mnemonic: "DEC",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x3B,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x3C,
+        mnemonic: "BIT",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x3D,
+        mnemonic: "AND",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x3E,
+        mnemonic: "ROL",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x3F,
+        mnemonic: "BBR3",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x40,
+        mnemonic: "RTI",
+        cycles: 6,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x41,
+        mnemonic: "EOR",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x42,
+        mnemonic: "NOP",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x43,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x44,
+        mnemonic: "NOP",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x45,
+        mnemonic: "EOR",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x46,
+        mnemonic: "LSR",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x47,
+        mnemonic: "RMB4",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x48,
+        mnemonic: "PHA",
+        cycles: 3,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x49,
+        mnemonic: "EOR",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x4A,
+        mnemonic: "LSR",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x4B,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x4C,
+        mnemonic: "JMP",
+        cycles: 3,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x4D,
+        mnemonic: "EOR",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x4E,
+        mnemonic: "LSR",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x4F,
+        mnemonic: "BBR4",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x50,
+        mnemonic: "BVC",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x51,
+        mnemonic: "EOR",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x52,
+        mnemonic: "EOR",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageIndirect,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x53,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x54,
+        mnemonic: "NOP",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x55,
+        mnemonic: "EOR",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x56,
+        mnemonic: "LSR",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x57,
+        mnemonic: "RMB5",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x58,
+        mnemonic: "CLI",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x59,
+        mnemonic: "EOR",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x5A,
+        mnemonic: "PHY",
+        cycles: 3,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x5B,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x5C,
+        mnemonic: "NOP",
+        cycles: 8,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x5D,
+        mnemonic: "EOR",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x5E,
+        mnemonic: "LSR",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x5F,
+        mnemonic: "BBR5",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x60,
+        mnemonic: "RTS",
+        cycles: 6,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x61,
+        mnemonic: "ADC",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x62,
+        mnemonic: "NOP",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x63,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x64,
+        mnemonic: "STZ",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x65,
+        mnemonic: "ADC",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x66,
+        mnemonic: "ROR",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x67,
+        mnemonic: "RMB6",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x68,
+        mnemonic: "PLA",
+        cycles: 4,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x69,
+        mnemonic: "ADC",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x6A,
+        mnemonic: "ROR",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x6B,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x6C,
+        mnemonic: "JMP",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::Indirect,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x6D,
+        mnemonic: "ADC",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x6E,
+        mnemonic: "ROR",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x6F,
+        mnemonic: "BBR6",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x70,
+        mnemonic: "BVS",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x71,
+        mnemonic: "ADC",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x72,
+        mnemonic: "ADC",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageIndirect,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x73,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x74,
+        mnemonic: "STZ",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x75,
+        mnemonic: "ADC",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x76,
+        mnemonic: "ROR",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x77,
+        mnemonic: "RMB7",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x78,
+        mnemonic: "SEI",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x79,
+        mnemonic: "ADC",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x7A,
+        mnemonic: "PLY",
+        cycles: 4,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x7B,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x7C,
+        mnemonic: "JMP",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteIndexedIndirect,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x7D,
+        mnemonic: "ADC",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x7E,
+        mnemonic: "ROR",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x7F,
+        mnemonic: "BBR7",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x80,
+        mnemonic: "BRA",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x81,
+        mnemonic: "STA",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectX,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x82,
+        mnemonic: "NOP",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x83,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x84,
+        mnemonic: "STY",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x85,
+        mnemonic: "STA",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x86,
+        mnemonic: "STX",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x87,
+        mnemonic: "SMB0",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x88,
+        mnemonic: "DEY",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x89,
+        mnemonic: "BIT",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x8A,
+        mnemonic: "TXA",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x8B,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x8C,
+        mnemonic: "STY",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x8D,
+        mnemonic: "STA",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x8E,
+        mnemonic: "STX",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x8F,
+        mnemonic: "BBS0",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x90,
+        mnemonic: "BCC",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x91,
+        mnemonic: "STA",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectY,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x92,
+        mnemonic: "STA",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageIndirect,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x93,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x94,
+        mnemonic: "STY",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x95,
+        mnemonic: "STA",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x96,
+        mnemonic: "STX",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageY,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x97,
+        mnemonic: "SMB1",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x98,
+        mnemonic: "TYA",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x99,
+        mnemonic: "STA",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteY,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x9A,
+        mnemonic: "TXS",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x9B,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0x9C,
+        mnemonic: "STZ",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x9D,
+        mnemonic: "STA",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x9E,
+        mnemonic: "STZ",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::Write,
+        official: true,
+    },
+    Instruction {
+        opcode: 0x9F,
+        mnemonic: "BBS1",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xA0,
+        mnemonic: "LDY",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xA1,
+        mnemonic: "LDA",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xA2,
+        mnemonic: "LDX",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xA3,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xA4,
+        mnemonic: "LDY",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xA5,
+        mnemonic: "LDA",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xA6,
+        mnemonic: "LDX",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xA7,
+        mnemonic: "SMB2",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xA8,
+        mnemonic: "TAY",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xA9,
+        mnemonic: "LDA",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xAA,
+        mnemonic: "TAX",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xAB,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xAC,
+        mnemonic: "LDY",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xAD,
+        mnemonic: "LDA",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xAE,
+        mnemonic: "LDX",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xAF,
+        mnemonic: "BBS2",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xB0,
+        mnemonic: "BCS",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xB1,
+        mnemonic: "LDA",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xB2,
+        mnemonic: "LDA",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageIndirect,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xB3,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xB4,
+        mnemonic: "LDY",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xB5,
+        mnemonic: "LDA",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xB6,
+        mnemonic: "LDX",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xB7,
+        mnemonic: "SMB3",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xB8,
+        mnemonic: "CLV",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xB9,
+        mnemonic: "LDA",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xBA,
+        mnemonic: "TSX",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xBB,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xBC,
+        mnemonic: "LDY",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xBD,
+        mnemonic: "LDA",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xBE,
+        mnemonic: "LDX",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xBF,
+        mnemonic: "BBS3",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xC0,
+        mnemonic: "CPY",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xC1,
+        mnemonic: "CMP",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xC2,
+        mnemonic: "NOP",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xC3,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xC4,
+        mnemonic: "CPY",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xC5,
+        mnemonic: "CMP",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xC6,
+        mnemonic: "DEC",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xC7,
+        mnemonic: "SMB4",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xC8,
+        mnemonic: "INY",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xC9,
+        mnemonic: "CMP",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xCA,
+        mnemonic: "DEX",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xCB,
+        mnemonic: "WAI",
+        cycles: 3,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xCC,
+        mnemonic: "CPY",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xCD,
+        mnemonic: "CMP",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xCE,
+        mnemonic: "DEC",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xCF,
+        mnemonic: "BBS4",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xD0,
+        mnemonic: "BNE",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xD1,
+        mnemonic: "CMP",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xD2,
+        mnemonic: "CMP",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageIndirect,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xD3,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xD4,
+        mnemonic: "NOP",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xD5,
+        mnemonic: "CMP",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xD6,
+        mnemonic: "DEC",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xD7,
+        mnemonic: "SMB5",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xD8,
+        mnemonic: "CLD",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xD9,
+        mnemonic: "CMP",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xDA,
+        mnemonic: "PHX",
+        cycles: 3,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xDB,
+        mnemonic: "STP",
+        cycles: 3,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xDC,
+        mnemonic: "NOP",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xDD,
+        mnemonic: "CMP",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xDE,
+        mnemonic: "DEC",
+        cycles: 7,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xDF,
+        mnemonic: "BBS5",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xE0,
+        mnemonic: "CPX",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xE1,
+        mnemonic: "SBC",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xE2,
+        mnemonic: "NOP",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xE3,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xE4,
+        mnemonic: "CPX",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xE5,
+        mnemonic: "SBC",
+        cycles: 3,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xE6,
+        mnemonic: "INC",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xE7,
+        mnemonic: "SMB6",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xE8,
+        mnemonic: "INX",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xE9,
+        mnemonic: "SBC",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Immediate,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xEA,
+        mnemonic: "NOP",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xEB,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xEC,
+        mnemonic: "CPX",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xED,
+        mnemonic: "SBC",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xEE,
+        mnemonic: "INC",
+        cycles: 6,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xEF,
+        mnemonic: "BBS6",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xF0,
+        mnemonic: "BEQ",
+        cycles: 2,
+        bytes: Size::Two,
+        mode: AddressingMode::Relative,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xF1,
+        mnemonic: "SBC",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::IndirectY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xF2,
+        mnemonic: "SBC",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageIndirect,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xF3,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xF4,
+        mnemonic: "NOP",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xF5,
+        mnemonic: "SBC",
+        cycles: 4,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xF6,
+        mnemonic: "INC",
+        cycles: 6,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPageX,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xF7,
+        mnemonic: "SMB7",
+        cycles: 5,
+        bytes: Size::Two,
+        mode: AddressingMode::ZeroPage,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xF8,
+        mnemonic: "SED",
+        cycles: 2,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xF9,
+        mnemonic: "SBC",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteY,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xFA,
+        mnemonic: "PLX",
+        cycles: 4,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xFB,
+        mnemonic: "NOP",
+        cycles: 1,
+        bytes: Size::One,
+        mode: AddressingMode::None,
+        access: Access::None,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xFC,
+        mnemonic: "NOP",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::Absolute,
+        access: Access::Read,
+        official: false,
+    },
+    Instruction {
+        opcode: 0xFD,
+        mnemonic: "SBC",
+        cycles: 4,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::Read,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xFE,
+        mnemonic: "INC",
+        cycles: 7,
+        bytes: Size::Three,
+        mode: AddressingMode::AbsoluteX,
+        access: Access::ReadModifyWrite,
+        official: true,
+    },
+    Instruction {
+        opcode: 0xFF,
+        mnemonic: "BBS7",
+        cycles: 5,
+        bytes: Size::Three,
+        mode: AddressingMode::ZeroPageRelative,
+        access: Access::Read,
+        official: true,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_reads_operands_of_every_size() {
+        let nop = decode(&[0xEA]).unwrap();
+        assert_eq!((nop.mnemonic, nop.length, nop.operand), ("NOP", 1, 0));
+
+        let lda = decode(&[0xA9, 0x42]).unwrap();
+        assert_eq!((lda.mnemonic, lda.mode), ("LDA", AddressingMode::Immediate));
+        assert_eq!((lda.length, lda.operand), (2, 0x42));
+
+        let sta = decode(&[0x8D, 0x34, 0x12, 0xFF]).unwrap();
+        assert_eq!((sta.mnemonic, sta.mode), ("STA", AddressingMode::Absolute));
+        assert_eq!((sta.length, sta.operand), (3, 0x1234));
+        assert_eq!(sta.access, Access::Write);
+    }
+
+    #[test]
+    fn decode_needs_the_whole_instruction() {
+        assert_eq!(decode(&[]), None);
+        assert_eq!(decode(&[0xA9]), None);
+        assert_eq!(decode(&[0x8D, 0x34]), None);
+    }
+
+    #[test]
+    fn decode_for_uses_the_variants_table() {
+        let nmos = decode_for(Variant::Nmos6502, &[0x80, 0x10]).unwrap();
+        assert_eq!((nmos.mnemonic, nmos.official), ("NOP", false));
+
+        let cmos = decode_for(Variant::Wdc65C02, &[0x80, 0x10]).unwrap();
+        assert_eq!((cmos.mnemonic, cmos.official), ("BRA", true));
+        assert_eq!(cmos.mode, AddressingMode::Relative);
+
+        assert_eq!(decode(&[0x12]).unwrap().mnemonic, "JAM");
+        let ora = decode_for(Variant::Wdc65C02, &[0x12, 0x80]).unwrap();
+        assert_eq!(ora.mode, AddressingMode::ZeroPageIndirect);
+
+        let slo = decode(&[0x0F, 0x10, 0xFE]).unwrap();
+        assert_eq!((slo.mnemonic, slo.mode), ("SLO", AddressingMode::Absolute));
+        let bbr = decode_for(Variant::Wdc65C02, &[0x0F, 0x10, 0xFE]).unwrap();
+        assert_eq!(bbr.mode, AddressingMode::ZeroPageRelative);
+        assert_eq!((bbr.mnemonic, bbr.operand), ("BBR0", 0xFE10));
+    }
+
+    #[test]
+    fn only_the_65c02_clears_decimal_on_brk() {
+        let brk = decode(&[0x00]).unwrap();
+        assert_eq!(brk.flags_written, Flags::INTERRUPT);
+        assert_eq!(brk.flags_read, Flags::ALL);
+
+        let brk = decode_for(Variant::Wdc65C02, &[0x00]).unwrap();
+        assert_eq!(brk.flags_written, Flags::INTERRUPT | Flags::DECIMAL);
+    }
+
+    #[test]
+    fn flags_read_and_written() {
+        let adc = decode(&[0x69, 0x01]).unwrap();
+        assert_eq!(adc.flags_read, Flags::CARRY | Flags::DECIMAL);
+        assert!(adc.flags_written.contains(Flags::CARRY | Flags::OVERFLOW));
+        assert!(!adc.flags_written.contains(Flags::DECIMAL));
+
+        let bit = decode_for(Variant::Wdc65C02, &[0x89, 0x01]).unwrap();
+        assert_eq!(bit.flags_written, Flags::ZERO);
+
+        assert!(decode(&[0xEA]).unwrap().flags_written.is_empty());
+    }
+}
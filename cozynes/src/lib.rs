@@ -1,10 +1,18 @@
 #[macro_use]
 extern crate log;
 
+pub mod asm;
 pub mod bus;
 pub mod cpu;
+pub mod crash;
+pub mod disasm;
+pub mod error;
+pub mod history;
 pub mod instruction;
+pub mod mapper;
 pub mod mem;
 pub mod rom;
 
 pub mod trace;
+
+pub use error::Error;
@@ -0,0 +1,172 @@
+use crate::{
+    rom::{self, Mirroring, Rom},
+    Error,
+};
+
+mod axrom;
+mod cnrom;
+mod gxrom;
+mod mmc1;
+mod mmc3;
+mod nrom;
+mod uxrom;
+mod vrc;
+mod vrc4;
+mod vrc6;
+mod vrc7;
+
+pub use self::{
+    axrom::Axrom, cnrom::Cnrom, gxrom::Gxrom, mmc1::Mmc1, mmc3::Mmc3, nrom::Nrom, uxrom::Uxrom,
+    vrc4::Vrc4, vrc6::Vrc6, vrc7::Vrc7,
+};
+
+/// The NES 2.0 submapper that marks a discrete logic board as having bus
+/// conflicts. Other submappers, including an iNES header's 0, have none.
+const BUS_CONFLICTS: u8 = 2;
+
+/// The circuitry of a cartridge board, which decides what the CPU sees at
+/// $4020-$FFFF and the PPU at $0000-$1FFF.
+pub trait Mapper: std::fmt::Debug {
+    /// Reads from $4020-$FFFF.
+    fn cpu_read(&self, addr: u16) -> Result<u8, Error>;
+    /// Writes to $4020-$FFFF, which is how most boards are controlled.
+    fn cpu_write(&mut self, addr: u16, value: u8) -> Result<(), Error>;
+    /// Reads pattern table memory at $0000-$1FFF.
+    fn ppu_read(&mut self, addr: u16) -> u8;
+    /// Writes pattern table memory at $0000-$1FFF. Writes to CHR ROM are
+    /// ignored.
+    fn ppu_write(&mut self, addr: u16, value: u8);
+    /// How the nametables are currently mirrored.
+    fn mirroring(&self) -> Mirroring;
+    /// Whether the board is asserting the IRQ line.
+    fn irq(&self) -> bool {
+        false
+    }
+    /// Called after the CPU spent `cycles` cycles.
+    fn clock(&mut self, _cycles: usize) {}
+}
+
+/// The value a discrete logic board latches on a write to its ROM. With bus
+/// conflicts the ROM drives the data bus at the same time, and a bit only
+/// stays set when both drive it high.
+fn bus_conflict(conflicts: bool, rom: u8, value: u8) -> u8 {
+    if conflicts {
+        rom & value
+    } else {
+        value
+    }
+}
+
+/// The index of `addr` within bank `bank` of `size` bytes in `len` bytes of
+/// memory. Banks past the end wrap around, as if the upper bank lines were
+/// not connected.
+fn bank_index(len: usize, size: usize, bank: usize, addr: u16) -> usize {
+    (bank * size + addr as usize % size) % len
+}
+
+/// Builds the board for the mapper number in the header of `rom`.
+pub fn from_rom(rom: Rom) -> Result<Box<dyn Mapper>, rom::Error> {
+    match rom.mapper {
+        0 => Ok(Box::new(Nrom::new(rom))),
+        1 => Ok(Box::new(Mmc1::new(rom))),
+        2 => Ok(Box::new(Uxrom::new(rom)?)),
+        3 => Ok(Box::new(Cnrom::new(rom))),
+        4 => Ok(Box::new(Mmc3::new(rom))),
+        7 => Ok(Box::new(Axrom::new(rom))),
+        21 | 22 | 23 | 25 => Ok(Box::new(Vrc4::new(rom))),
+        24 | 26 => Ok(Box::new(Vrc6::new(rom)?)),
+        66 => Ok(Box::new(Gxrom::new(rom))),
+        85 => Ok(Box::new(Vrc7::new(rom)?)),
+        mapper => Err(rom::Error::UnsupportedMapper(mapper)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A ROM with the number of each 8K PRG ROM page and 1K CHR ROM page in
+    /// all of its bytes, so a read shows which page is mapped.
+    pub(super) fn rom(mapper: u16, prg_rom: usize, chr_rom: usize) -> Rom {
+        Rom {
+            prg_rom: (0..prg_rom).map(|i| (i / 0x2000) as u8).collect(),
+            chr_rom: (0..chr_rom).map(|i| (i / 0x0400) as u8).collect(),
+            mapper,
+            submapper: 0,
+            mirroring: Mirroring::Horizontal,
+            prg_ram_size: 0,
+        }
+    }
+
+    #[test]
+    fn banks_past_the_end_wrap_around() {
+        assert_eq!(bank_index(0x8000, 0x4000, 1, 0xC123), 0x4123);
+        assert_eq!(bank_index(0x8000, 0x4000, 3, 0x8123), 0x4123);
+        assert_eq!(bank_index(0x2000, 0x0400, 9, 0x0001), 0x0401);
+    }
+
+    #[test]
+    fn bus_conflicts_and_the_written_value_with_rom() {
+        assert_eq!(bus_conflict(true, 0b1100, 0b1010), 0b1000);
+        assert_eq!(bus_conflict(false, 0b1100, 0b1010), 0b1010);
+    }
+
+    #[test]
+    fn unsupported_mappers_are_rejected() {
+        assert!(from_rom(rom(0, 0x4000, 0x2000)).is_ok());
+        assert!(matches!(
+            from_rom(rom(5, 0x4000, 0x2000)),
+            Err(rom::Error::UnsupportedMapper(5))
+        ));
+    }
+
+    #[test]
+    fn nrom_mirrors_16k_of_prg_rom() {
+        let nrom = Nrom::new(rom(0, 0x4000, 0x2000));
+        assert_eq!(nrom.cpu_read(0x8000).unwrap(), 0);
+        assert_eq!(nrom.cpu_read(0xA000).unwrap(), 1);
+        assert_eq!(nrom.cpu_read(0xC000).unwrap(), 0);
+        assert_eq!(nrom.cpu_read(0xE000).unwrap(), 1);
+
+        let nrom = Nrom::new(rom(0, 0x8000, 0x2000));
+        assert_eq!(nrom.cpu_read(0xC000).unwrap(), 2);
+        assert_eq!(nrom.cpu_read(0xFFFF).unwrap(), 3);
+        assert_eq!(
+            nrom.cpu_read(0x6000),
+            Err(Error::UnmappedRead { addr: 0x6000 })
+        );
+    }
+
+    #[test]
+    fn nrom_rom_is_read_only() {
+        let mut nrom = Nrom::new(rom(0, 0x4000, 0x2000));
+        assert_eq!(
+            nrom.cpu_write(0x8000, 1),
+            Err(Error::RomWrite {
+                addr: 0x8000,
+                value: 1
+            })
+        );
+        nrom.ppu_write(0x1C00, 0xFF);
+        assert_eq!(nrom.ppu_read(0x1C00), 7);
+
+        let mut nrom = Nrom::new(rom(0, 0x4000, 0));
+        nrom.ppu_write(0x1C00, 0xFF);
+        assert_eq!(nrom.ppu_read(0x1C00), 0xFF);
+    }
+
+    #[test]
+    fn nrom_mirroring_comes_from_the_header() {
+        for mirroring in [
+            Mirroring::Horizontal,
+            Mirroring::Vertical,
+            Mirroring::FourScreen,
+        ] {
+            let nrom = Nrom::new(Rom {
+                mirroring,
+                ..rom(0, 0x4000, 0x2000)
+            });
+            assert_eq!(nrom.mirroring(), mirroring);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use super::{bank_index, bus_conflict, Mapper, BUS_CONFLICTS};
+use crate::{
+    rom::{Mirroring, Rom},
+    Error,
+};
+
+const PRG_BANK_SIZE: usize = 0x8000;
+
+/// Mapper 7, a switchable 32K PRG ROM bank and single-screen mirroring
+/// selected by the same register, with 8K of CHR RAM.
+#[derive(Debug)]
+pub struct Axrom {
+    prg_rom: Vec<u8>,
+    chr: Vec<u8>,
+    chr_ram: bool,
+    bus_conflicts: bool,
+    register: u8,
+}
+
+impl Axrom {
+    pub fn new(rom: Rom) -> Self {
+        let chr_ram = rom.chr_rom.is_empty();
+        Self {
+            prg_rom: rom.prg_rom,
+            chr: if chr_ram {
+                vec![0; 0x2000]
+            } else {
+                rom.chr_rom
+            },
+            chr_ram,
+            bus_conflicts: rom.submapper == BUS_CONFLICTS,
+            register: 0,
+        }
+    }
+}
+
+impl Mapper for Axrom {
+    fn cpu_read(&self, addr: u16) -> Result<u8, Error> {
+        match addr {
+            0x8000..=0xFFFF => {
+                let bank = (self.register & 0x07) as usize;
+                Ok(self.prg_rom[bank_index(self.prg_rom.len(), PRG_BANK_SIZE, bank, addr)])
+            }
+            _ => Err(Error::UnmappedRead { addr }),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> Result<(), Error> {
+        match addr {
+            0x8000..=0xFFFF => {
+                self.register = bus_conflict(self.bus_conflicts, self.cpu_read(addr)?, value);
+                Ok(())
+            }
+            _ => Err(Error::UnmappedWrite { addr, value }),
+        }
+    }
+
+    fn ppu_read(&mut self, addr: u16) -> u8 {
+        self.chr[addr as usize % self.chr.len()]
+    }
+
+    fn ppu_write(&mut self, addr: u16, value: u8) {
+        if self.chr_ram {
+            let len = self.chr.len();
+            self.chr[addr as usize % len] = value;
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        if self.register & 0x10 == 0 {
+            Mirroring::SingleScreenLower
+        } else {
+            Mirroring::SingleScreenUpper
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapper::tests::rom;
+
+    #[test]
+    fn switches_the_32k_bank_and_the_screen() {
+        let mut axrom = Axrom::new(rom(7, 0x40000, 0));
+        assert_eq!(axrom.cpu_read(0x8000).unwrap(), 0);
+        assert_eq!(axrom.mirroring(), Mirroring::SingleScreenLower);
+
+        axrom.cpu_write(0x8000, 0x13).unwrap();
+        assert_eq!(axrom.cpu_read(0x8000).unwrap(), 12);
+        assert_eq!(axrom.cpu_read(0xFFFF).unwrap(), 15);
+        assert_eq!(axrom.mirroring(), Mirroring::SingleScreenUpper);
+    }
+
+    #[test]
+    fn bus_conflicts_and_the_register_with_rom() {
+        let mut axrom = Axrom::new(Rom {
+            submapper: BUS_CONFLICTS,
+            ..rom(7, 0x40000, 0)
+        });
+        // ROM holds 0b11 at $E000
+        axrom.cpu_write(0xE000, 0x13).unwrap();
+        assert_eq!(axrom.cpu_read(0x8000).unwrap(), 12);
+        assert_eq!(axrom.mirroring(), Mirroring::SingleScreenLower);
+    }
+}
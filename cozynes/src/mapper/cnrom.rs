@@ -0,0 +1,110 @@
+use super::{bank_index, bus_conflict, Mapper, BUS_CONFLICTS};
+use crate::{
+    rom::{Mirroring, Rom},
+    Error,
+};
+
+const CHR_BANK_SIZE: usize = 0x2000;
+
+/// Mapper 3, NROM's fixed PRG ROM with a switchable 8K CHR ROM bank.
+#[derive(Debug)]
+pub struct Cnrom {
+    prg_rom: Vec<u8>,
+    chr: Vec<u8>,
+    chr_ram: bool,
+    mirroring: Mirroring,
+    bus_conflicts: bool,
+    bank: u8,
+}
+
+impl Cnrom {
+    pub fn new(rom: Rom) -> Self {
+        let chr_ram = rom.chr_rom.is_empty();
+        Self {
+            prg_rom: rom.prg_rom,
+            chr: if chr_ram {
+                vec![0; 0x2000]
+            } else {
+                rom.chr_rom
+            },
+            chr_ram,
+            mirroring: rom.mirroring,
+            bus_conflicts: rom.submapper == BUS_CONFLICTS,
+            bank: 0,
+        }
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        bank_index(self.chr.len(), CHR_BANK_SIZE, self.bank as usize, addr)
+    }
+}
+
+impl Mapper for Cnrom {
+    fn cpu_read(&self, addr: u16) -> Result<u8, Error> {
+        match addr {
+            0x8000..=0xFFFF => Ok(self.prg_rom[(addr - 0x8000) as usize % self.prg_rom.len()]),
+            _ => Err(Error::UnmappedRead { addr }),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> Result<(), Error> {
+        match addr {
+            0x8000..=0xFFFF => {
+                self.bank = bus_conflict(self.bus_conflicts, self.cpu_read(addr)?, value);
+                Ok(())
+            }
+            _ => Err(Error::UnmappedWrite { addr, value }),
+        }
+    }
+
+    fn ppu_read(&mut self, addr: u16) -> u8 {
+        self.chr[self.chr_index(addr)]
+    }
+
+    fn ppu_write(&mut self, addr: u16, value: u8) {
+        if self.chr_ram {
+            let index = self.chr_index(addr);
+            self.chr[index] = value;
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapper::tests::rom;
+
+    #[test]
+    fn switches_the_chr_bank() {
+        let mut cnrom = Cnrom::new(rom(3, 0x8000, 0x8000));
+        assert_eq!(cnrom.ppu_read(0x0000), 0);
+
+        cnrom.cpu_write(0xE000, 3).unwrap();
+        assert_eq!(cnrom.ppu_read(0x0000), 24);
+        assert_eq!(cnrom.ppu_read(0x1FFF), 31);
+        assert_eq!(cnrom.cpu_read(0x8000).unwrap(), 0);
+    }
+
+    #[test]
+    fn bus_conflicts_and_the_bank_number_with_rom() {
+        let mut cnrom = Cnrom::new(Rom {
+            submapper: BUS_CONFLICTS,
+            ..rom(3, 0x8000, 0x8000)
+        });
+        // ROM holds 0b10 at $C000 and 0b11 at $E000
+        cnrom.cpu_write(0xC000, 0b11).unwrap();
+        assert_eq!(cnrom.ppu_read(0x0000), 16);
+        cnrom.cpu_write(0xE000, 0b11).unwrap();
+        assert_eq!(cnrom.ppu_read(0x0000), 24);
+    }
+
+    #[test]
+    fn mirroring_comes_from_the_header() {
+        let cnrom = Cnrom::new(rom(3, 0x8000, 0x8000));
+        assert_eq!(cnrom.mirroring(), Mirroring::Horizontal);
+    }
+}
@@ -0,0 +1,116 @@
+use super::{bank_index, bus_conflict, Mapper, BUS_CONFLICTS};
+use crate::{
+    rom::{Mirroring, Rom},
+    Error,
+};
+
+const PRG_BANK_SIZE: usize = 0x8000;
+const CHR_BANK_SIZE: usize = 0x2000;
+
+/// Mapper 66, a switchable 32K PRG ROM bank and 8K CHR ROM bank selected by
+/// the same register.
+#[derive(Debug)]
+pub struct Gxrom {
+    prg_rom: Vec<u8>,
+    chr: Vec<u8>,
+    chr_ram: bool,
+    mirroring: Mirroring,
+    bus_conflicts: bool,
+    register: u8,
+}
+
+impl Gxrom {
+    pub fn new(rom: Rom) -> Self {
+        let chr_ram = rom.chr_rom.is_empty();
+        Self {
+            prg_rom: rom.prg_rom,
+            chr: if chr_ram {
+                vec![0; 0x2000]
+            } else {
+                rom.chr_rom
+            },
+            chr_ram,
+            mirroring: rom.mirroring,
+            bus_conflicts: rom.submapper == BUS_CONFLICTS,
+            register: 0,
+        }
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        let bank = (self.register & 0x03) as usize;
+        bank_index(self.chr.len(), CHR_BANK_SIZE, bank, addr)
+    }
+}
+
+impl Mapper for Gxrom {
+    fn cpu_read(&self, addr: u16) -> Result<u8, Error> {
+        match addr {
+            0x8000..=0xFFFF => {
+                let bank = ((self.register >> 4) & 0x03) as usize;
+                Ok(self.prg_rom[bank_index(self.prg_rom.len(), PRG_BANK_SIZE, bank, addr)])
+            }
+            _ => Err(Error::UnmappedRead { addr }),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> Result<(), Error> {
+        match addr {
+            0x8000..=0xFFFF => {
+                self.register = bus_conflict(self.bus_conflicts, self.cpu_read(addr)?, value);
+                Ok(())
+            }
+            _ => Err(Error::UnmappedWrite { addr, value }),
+        }
+    }
+
+    fn ppu_read(&mut self, addr: u16) -> u8 {
+        self.chr[self.chr_index(addr)]
+    }
+
+    fn ppu_write(&mut self, addr: u16, value: u8) {
+        if self.chr_ram {
+            let index = self.chr_index(addr);
+            self.chr[index] = value;
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapper::tests::rom;
+
+    #[test]
+    fn switches_the_prg_and_chr_banks() {
+        let mut gxrom = Gxrom::new(rom(66, 0x20000, 0x8000));
+        gxrom.cpu_write(0x8000, 0x21).unwrap();
+        assert_eq!(gxrom.cpu_read(0x8000).unwrap(), 8);
+        assert_eq!(gxrom.cpu_read(0xFFFF).unwrap(), 11);
+        assert_eq!(gxrom.ppu_read(0x0000), 8);
+    }
+
+    #[test]
+    fn bus_conflicts_and_the_register_with_rom() {
+        let mut gxrom = Gxrom::new(Rom {
+            submapper: BUS_CONFLICTS,
+            ..rom(66, 0x20000, 0x8000)
+        });
+        // ROM holds 0b11 at $E000
+        gxrom.cpu_write(0xE000, 0x21).unwrap();
+        assert_eq!(gxrom.cpu_read(0x8000).unwrap(), 0);
+        assert_eq!(gxrom.ppu_read(0x0000), 8);
+    }
+
+    #[test]
+    fn mirroring_comes_from_the_header() {
+        let gxrom = Gxrom::new(Rom {
+            mirroring: Mirroring::Vertical,
+            ..rom(66, 0x20000, 0x8000)
+        });
+        assert_eq!(gxrom.mirroring(), Mirroring::Vertical);
+    }
+}
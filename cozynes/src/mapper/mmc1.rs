@@ -0,0 +1,337 @@
+use super::{bank_index, Mapper};
+use crate::{
+    rom::{Mirroring, Rom},
+    Error,
+};
+
+const PRG_BANK_SIZE: usize = 0x4000;
+const CHR_BANK_SIZE: usize = 0x1000;
+const PRG_RAM_BANK_SIZE: usize = 0x2000;
+
+/// Mapper 1, the MMC1 used by the SxROM boards. Its registers are loaded one
+/// bit at a time through a serial shift register at $8000-$FFFF.
+///
+/// On SUROM, SOROM and SXROM the upper bits of the CHR bank registers
+/// select a 256K PRG ROM half and the PRG RAM bank instead, as those boards
+/// have only 8K of CHR RAM.
+#[derive(Debug)]
+pub struct Mmc1 {
+    prg_rom: Vec<u8>,
+    prg_ram: Vec<u8>,
+    chr: Vec<u8>,
+    chr_ram: bool,
+    shift: u8,
+    /// Bits written to `shift` so far.
+    writes: u8,
+    control: u8,
+    chr_bank_0: u8,
+    chr_bank_1: u8,
+    prg_bank: u8,
+    /// Set when the PPU last fetched from the upper pattern table. In 4K CHR
+    /// mode this picks the register the SxROM board bits are taken from.
+    chr_upper: bool,
+    /// CPU cycles since power on.
+    cycle: usize,
+    last_write: Option<usize>,
+}
+
+impl Mmc1 {
+    pub fn new(rom: Rom) -> Self {
+        let chr_ram = rom.chr_rom.is_empty();
+        Self {
+            prg_rom: rom.prg_rom,
+            prg_ram: vec![0; rom.prg_ram_size],
+            chr: if chr_ram {
+                vec![0; 0x2000]
+            } else {
+                rom.chr_rom
+            },
+            chr_ram,
+            shift: 0,
+            writes: 0,
+            // the last bank is fixed at $C000 on power on
+            control: 0x0C,
+            chr_bank_0: 0,
+            chr_bank_1: 0,
+            prg_bank: 0,
+            chr_upper: false,
+            cycle: 0,
+            last_write: None,
+        }
+    }
+
+    /// The CHR bank register whose upper bits the SxROM boards use.
+    fn board_register(&self) -> u8 {
+        if self.control & 0x10 != 0 && self.chr_upper {
+            self.chr_bank_1
+        } else {
+            self.chr_bank_0
+        }
+    }
+
+    fn prg_ram_enabled(&self) -> bool {
+        !self.prg_ram.is_empty() && self.prg_bank & 0x10 == 0
+    }
+
+    fn prg_ram_index(&self, addr: u16) -> usize {
+        let bank = match self.prg_ram.len() {
+            // SOROM
+            0x4000 => (self.board_register() >> 3) & 0x01,
+            // SXROM
+            0x8000 => (self.board_register() >> 2) & 0x03,
+            _ => 0,
+        };
+        bank_index(self.prg_ram.len(), PRG_RAM_BANK_SIZE, bank as usize, addr)
+    }
+
+    fn prg_rom_index(&self, addr: u16) -> usize {
+        let bank = (self.prg_bank & 0x0F) as usize;
+        let bank = match (self.control >> 2) & 0x03 {
+            // 32K at $8000, ignoring the low bit of the bank number
+            0 | 1 => (bank & !1) | (addr >= 0xC000) as usize,
+            // first bank fixed at $8000
+            2 if addr < 0xC000 => 0,
+            2 => bank,
+            // last bank fixed at $C000
+            _ if addr < 0xC000 => bank,
+            _ => 0x0F,
+        };
+        // SUROM and SXROM
+        let outer = if self.prg_rom.len() > 0x40000 {
+            ((self.board_register() >> 4) & 0x01) as usize * 16
+        } else {
+            0
+        };
+        bank_index(self.prg_rom.len(), PRG_BANK_SIZE, outer + bank, addr)
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        let bank = if self.control & 0x10 == 0 {
+            // 8K, ignoring the low bit of the bank number
+            (self.chr_bank_0 & !1) | (addr >= 0x1000) as u8
+        } else if addr < 0x1000 {
+            self.chr_bank_0
+        } else {
+            self.chr_bank_1
+        };
+        bank_index(self.chr.len(), CHR_BANK_SIZE, bank as usize, addr)
+    }
+
+    fn write_register(&mut self, addr: u16, value: u8) {
+        // the serial port ignores a write on the cycle after another, so
+        // only the first write of a read-modify-write instruction counts
+        let consecutive = self.last_write.is_some_and(|last| last + 1 == self.cycle);
+        self.last_write = Some(self.cycle);
+        if consecutive {
+            return;
+        }
+
+        if value & 0x80 != 0 {
+            self.shift = 0;
+            self.writes = 0;
+            self.control |= 0x0C;
+            return;
+        }
+
+        self.shift |= (value & 0x01) << self.writes;
+        self.writes += 1;
+        if self.writes < 5 {
+            return;
+        }
+
+        let value = self.shift;
+        self.shift = 0;
+        self.writes = 0;
+        match addr {
+            0x8000..=0x9FFF => self.control = value,
+            0xA000..=0xBFFF => self.chr_bank_0 = value,
+            0xC000..=0xDFFF => self.chr_bank_1 = value,
+            _ => self.prg_bank = value,
+        }
+    }
+}
+
+impl Mapper for Mmc1 {
+    fn cpu_read(&self, addr: u16) -> Result<u8, Error> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => Ok(self.prg_ram[self.prg_ram_index(addr)]),
+            0x8000..=0xFFFF => Ok(self.prg_rom[self.prg_rom_index(addr)]),
+            _ => Err(Error::UnmappedRead { addr }),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> Result<(), Error> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => {
+                let index = self.prg_ram_index(addr);
+                self.prg_ram[index] = value;
+                Ok(())
+            }
+            0x8000..=0xFFFF => {
+                self.write_register(addr, value);
+                Ok(())
+            }
+            _ => Err(Error::UnmappedWrite { addr, value }),
+        }
+    }
+
+    fn ppu_read(&mut self, addr: u16) -> u8 {
+        self.chr_upper = addr & 0x1000 != 0;
+        self.chr[self.chr_index(addr)]
+    }
+
+    fn ppu_write(&mut self, addr: u16, value: u8) {
+        self.chr_upper = addr & 0x1000 != 0;
+        if self.chr_ram {
+            let index = self.chr_index(addr);
+            self.chr[index] = value;
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        match self.control & 0x03 {
+            0 => Mirroring::SingleScreenLower,
+            1 => Mirroring::SingleScreenUpper,
+            2 => Mirroring::Vertical,
+            _ => Mirroring::Horizontal,
+        }
+    }
+
+    fn clock(&mut self, cycles: usize) {
+        self.cycle += cycles;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapper::tests::rom;
+
+    /// Loads `value` into the register at `addr` through the serial port.
+    fn write(mmc1: &mut Mmc1, addr: u16, value: u8) {
+        for bit in 0..5 {
+            mmc1.clock(2);
+            mmc1.cpu_write(addr, (value >> bit) & 0x01).unwrap();
+        }
+    }
+
+    fn mmc1(prg_rom: usize, chr_rom: usize) -> Mmc1 {
+        Mmc1::new(Rom {
+            prg_ram_size: 0x2000,
+            ..rom(1, prg_rom, chr_rom)
+        })
+    }
+
+    #[test]
+    fn registers_load_after_five_writes() {
+        let mut mmc1 = mmc1(0x40000, 0x20000);
+        for bit in [1, 1, 0, 0] {
+            mmc1.clock(2);
+            mmc1.cpu_write(0xE000, bit).unwrap();
+            assert_eq!(mmc1.cpu_read(0x8000).unwrap(), 0);
+        }
+        mmc1.clock(2);
+        mmc1.cpu_write(0xE000, 0).unwrap();
+        assert_eq!(mmc1.cpu_read(0x8000).unwrap(), 6);
+    }
+
+    #[test]
+    fn a_write_with_bit_7_resets_the_shift_register() {
+        let mut mmc1 = mmc1(0x40000, 0x20000);
+        write(&mut mmc1, 0x8000, 0x08);
+        for bit in [1, 1] {
+            mmc1.clock(2);
+            mmc1.cpu_write(0xE000, bit).unwrap();
+        }
+        mmc1.clock(2);
+        mmc1.cpu_write(0x8000, 0x80).unwrap();
+        // the reset also fixes the last bank at $C000 again
+        assert_eq!(mmc1.cpu_read(0xC000).unwrap(), 30);
+
+        write(&mut mmc1, 0xE000, 0x02);
+        assert_eq!(mmc1.cpu_read(0x8000).unwrap(), 4);
+    }
+
+    #[test]
+    fn writes_on_consecutive_cycles_are_ignored() {
+        let mut mmc1 = mmc1(0x40000, 0x20000);
+        for bit in [1, 1, 0, 0, 0] {
+            mmc1.clock(2);
+            mmc1.cpu_write(0xE000, bit).unwrap();
+            // the second write of a read-modify-write instruction
+            mmc1.clock(1);
+            mmc1.cpu_write(0xE000, 0x01).unwrap();
+        }
+        assert_eq!(mmc1.cpu_read(0x8000).unwrap(), 6);
+    }
+
+    #[test]
+    fn prg_rom_banking_modes() {
+        let mut mmc1 = mmc1(0x40000, 0x20000);
+        write(&mut mmc1, 0xE000, 3);
+        // switchable bank at $8000, last bank fixed at $C000
+        assert_eq!(mmc1.cpu_read(0x8000).unwrap(), 6);
+        assert_eq!(mmc1.cpu_read(0xC000).unwrap(), 30);
+
+        // first bank fixed at $8000, switchable bank at $C000
+        write(&mut mmc1, 0x8000, 0x08);
+        assert_eq!(mmc1.cpu_read(0x8000).unwrap(), 0);
+        assert_eq!(mmc1.cpu_read(0xC000).unwrap(), 6);
+
+        // 32K, ignoring the low bit of the bank number
+        write(&mut mmc1, 0x8000, 0x00);
+        assert_eq!(mmc1.cpu_read(0x8000).unwrap(), 4);
+        assert_eq!(mmc1.cpu_read(0xC000).unwrap(), 6);
+    }
+
+    #[test]
+    fn chr_banking_modes() {
+        let mut mmc1 = mmc1(0x40000, 0x20000);
+        write(&mut mmc1, 0xA000, 3);
+        write(&mut mmc1, 0xC000, 5);
+        // 8K, ignoring the low bit of the bank number
+        assert_eq!(mmc1.ppu_read(0x0000), 8);
+        assert_eq!(mmc1.ppu_read(0x1000), 12);
+
+        write(&mut mmc1, 0x8000, 0x10);
+        assert_eq!(mmc1.ppu_read(0x0000), 12);
+        assert_eq!(mmc1.ppu_read(0x1000), 20);
+    }
+
+    #[test]
+    fn mirroring() {
+        let mut mmc1 = mmc1(0x40000, 0x20000);
+        for (control, mirroring) in [
+            (0, Mirroring::SingleScreenLower),
+            (1, Mirroring::SingleScreenUpper),
+            (2, Mirroring::Vertical),
+            (3, Mirroring::Horizontal),
+        ] {
+            write(&mut mmc1, 0x8000, control);
+            assert_eq!(mmc1.mirroring(), mirroring);
+        }
+    }
+
+    #[test]
+    fn prg_ram_can_be_disabled() {
+        let mut mmc1 = mmc1(0x40000, 0x20000);
+        mmc1.cpu_write(0x6000, 0x42).unwrap();
+        assert_eq!(mmc1.cpu_read(0x6000).unwrap(), 0x42);
+
+        write(&mut mmc1, 0xE000, 0x10);
+        assert_eq!(
+            mmc1.cpu_read(0x6000),
+            Err(Error::UnmappedRead { addr: 0x6000 })
+        );
+    }
+
+    #[test]
+    fn surom_selects_the_prg_rom_half_with_the_chr_register() {
+        let mut mmc1 = mmc1(0x80000, 0);
+        assert_eq!(mmc1.cpu_read(0xC000).unwrap(), 30);
+
+        write(&mut mmc1, 0xA000, 0x10);
+        assert_eq!(mmc1.cpu_read(0x8000).unwrap(), 32);
+        assert_eq!(mmc1.cpu_read(0xC000).unwrap(), 62);
+    }
+}
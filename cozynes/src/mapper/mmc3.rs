@@ -0,0 +1,405 @@
+use super::{bank_index, Mapper};
+use crate::{
+    rom::{Mirroring, Rom},
+    Error,
+};
+
+const PRG_BANK_SIZE: usize = 0x2000;
+const CHR_BANK_SIZE: usize = 0x0400;
+/// CPU cycles PPU A12 has to stay low before a rise clocks the IRQ counter,
+/// which filters out the short drops between sprite pattern fetches.
+const A12_FILTER: usize = 3;
+/// The NES 2.0 submapper of boards with the older MMC3A.
+const SUBMAPPER_MMC3A: u8 = 4;
+
+/// Which chip revision's IRQ behaviour to emulate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Revision {
+    /// MMC3B and MMC3C, made by Sharp. The IRQ fires whenever the counter
+    /// is 0 after being clocked, including every reload from a latch of 0.
+    Sharp,
+    /// MMC3A, made by NEC. The IRQ only fires when the counter is clocked to
+    /// 0 by a decrement or by a reload requested through $C001.
+    Nec,
+}
+
+/// Mapper 4, the MMC3 used by the TxROM boards. Has two switchable 8K PRG
+/// ROM banks, six CHR banks of 1K and 2K, and an IRQ counter clocked by
+/// rising edges of PPU address line A12, once per scanline.
+#[derive(Debug)]
+pub struct Mmc3 {
+    prg_rom: Vec<u8>,
+    prg_ram: Vec<u8>,
+    chr: Vec<u8>,
+    chr_ram: bool,
+    four_screen: bool,
+    revision: Revision,
+    bank_select: u8,
+    /// R0-R7, the bank numbers written through $8001.
+    banks: [u8; 8],
+    horizontal: bool,
+    prg_ram_enabled: bool,
+    prg_ram_protected: bool,
+    irq_latch: u8,
+    irq_counter: u8,
+    irq_reload: bool,
+    irq_enabled: bool,
+    irq: bool,
+    /// CPU cycles since power on.
+    cycle: usize,
+    a12: bool,
+    /// The cycle at which A12 last went low.
+    a12_low_at: usize,
+}
+
+impl Mmc3 {
+    pub fn new(rom: Rom) -> Self {
+        let chr_ram = rom.chr_rom.is_empty();
+        Self {
+            prg_rom: rom.prg_rom,
+            prg_ram: vec![0; rom.prg_ram_size],
+            chr: if chr_ram {
+                vec![0; 0x2000]
+            } else {
+                rom.chr_rom
+            },
+            chr_ram,
+            four_screen: rom.mirroring == Mirroring::FourScreen,
+            revision: if rom.submapper == SUBMAPPER_MMC3A {
+                Revision::Nec
+            } else {
+                Revision::Sharp
+            },
+            bank_select: 0,
+            banks: [0; 8],
+            horizontal: rom.mirroring == Mirroring::Horizontal,
+            prg_ram_enabled: true,
+            prg_ram_protected: false,
+            irq_latch: 0,
+            irq_counter: 0,
+            irq_reload: false,
+            irq_enabled: false,
+            irq: false,
+            cycle: 0,
+            a12: false,
+            a12_low_at: 0,
+        }
+    }
+
+    fn prg_rom_index(&self, addr: u16) -> usize {
+        let second_last = (self.prg_rom.len() / PRG_BANK_SIZE).saturating_sub(2);
+        let swapped = self.bank_select & 0x40 != 0;
+        let bank = match addr {
+            0x8000..=0x9FFF if swapped => second_last,
+            0x8000..=0x9FFF => self.banks[6] as usize,
+            0xA000..=0xBFFF => self.banks[7] as usize,
+            0xC000..=0xDFFF if swapped => self.banks[6] as usize,
+            0xC000..=0xDFFF => second_last,
+            _ => second_last + 1,
+        };
+        bank_index(self.prg_rom.len(), PRG_BANK_SIZE, bank, addr)
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        // inversion swaps the 2K and 1K banks between the pattern tables
+        let slot = if self.bank_select & 0x80 != 0 {
+            addr ^ 0x1000
+        } else {
+            addr
+        } >> 10;
+        let bank = match slot & 0x07 {
+            0 => self.banks[0] & !1,
+            1 => self.banks[0] | 1,
+            2 => self.banks[1] & !1,
+            3 => self.banks[1] | 1,
+            slot => self.banks[slot as usize - 2],
+        };
+        bank_index(self.chr.len(), CHR_BANK_SIZE, bank as usize, addr)
+    }
+
+    fn prg_ram_index(&self, addr: u16) -> usize {
+        (addr - 0x6000) as usize % self.prg_ram.len()
+    }
+
+    /// Tracks A12 of a PPU access and clocks the IRQ counter on a rise.
+    fn watch_a12(&mut self, addr: u16) {
+        let a12 = addr & 0x1000 != 0;
+        if a12 && !self.a12 && self.cycle - self.a12_low_at >= A12_FILTER {
+            self.clock_irq_counter();
+        } else if !a12 && self.a12 {
+            self.a12_low_at = self.cycle;
+        }
+        self.a12 = a12;
+    }
+
+    fn clock_irq_counter(&mut self) {
+        let reload = self.irq_reload;
+        let before = self.irq_counter;
+        if self.irq_counter == 0 || self.irq_reload {
+            self.irq_counter = self.irq_latch;
+            self.irq_reload = false;
+        } else {
+            self.irq_counter -= 1;
+        }
+
+        let fire = match self.revision {
+            Revision::Sharp => self.irq_counter == 0,
+            Revision::Nec => self.irq_counter == 0 && (before != 0 || reload),
+        };
+        if fire && self.irq_enabled {
+            self.irq = true;
+        }
+    }
+
+    fn write_register(&mut self, addr: u16, value: u8) {
+        let odd = addr & 0x01 != 0;
+        match addr {
+            0x8000..=0x9FFF if odd => self.banks[(self.bank_select & 0x07) as usize] = value,
+            0x8000..=0x9FFF => self.bank_select = value,
+            0xA000..=0xBFFF if odd => {
+                self.prg_ram_enabled = value & 0x80 != 0;
+                self.prg_ram_protected = value & 0x40 != 0;
+            }
+            0xA000..=0xBFFF => self.horizontal = value & 0x01 != 0,
+            0xC000..=0xDFFF if odd => {
+                self.irq_counter = 0;
+                self.irq_reload = true;
+            }
+            0xC000..=0xDFFF => self.irq_latch = value,
+            _ if odd => self.irq_enabled = true,
+            _ => {
+                self.irq_enabled = false;
+                self.irq = false;
+            }
+        }
+    }
+}
+
+impl Mapper for Mmc3 {
+    fn cpu_read(&self, addr: u16) -> Result<u8, Error> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled && !self.prg_ram.is_empty() => {
+                Ok(self.prg_ram[self.prg_ram_index(addr)])
+            }
+            0x8000..=0xFFFF => Ok(self.prg_rom[self.prg_rom_index(addr)]),
+            _ => Err(Error::UnmappedRead { addr }),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> Result<(), Error> {
+        match addr {
+            0x6000..=0x7FFF
+                if self.prg_ram_enabled && !self.prg_ram_protected && !self.prg_ram.is_empty() =>
+            {
+                let index = self.prg_ram_index(addr);
+                self.prg_ram[index] = value;
+                Ok(())
+            }
+            0x8000..=0xFFFF => {
+                self.write_register(addr, value);
+                Ok(())
+            }
+            _ => Err(Error::UnmappedWrite { addr, value }),
+        }
+    }
+
+    fn ppu_read(&mut self, addr: u16) -> u8 {
+        self.watch_a12(addr);
+        self.chr[self.chr_index(addr)]
+    }
+
+    fn ppu_write(&mut self, addr: u16, value: u8) {
+        self.watch_a12(addr);
+        if self.chr_ram {
+            let index = self.chr_index(addr);
+            self.chr[index] = value;
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        if self.four_screen {
+            Mirroring::FourScreen
+        } else if self.horizontal {
+            Mirroring::Horizontal
+        } else {
+            Mirroring::Vertical
+        }
+    }
+
+    fn irq(&self) -> bool {
+        self.irq
+    }
+
+    fn clock(&mut self, cycles: usize) {
+        self.cycle += cycles;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapper::tests::rom;
+
+    fn mmc3(submapper: u8) -> Mmc3 {
+        Mmc3::new(Rom {
+            submapper,
+            prg_ram_size: 0x2000,
+            ..rom(4, 0x40000, 0x40000)
+        })
+    }
+
+    /// The pattern fetches of a scanline, A12 low for the background and
+    /// rising for the sprites.
+    fn scanline(mmc3: &mut Mmc3) {
+        mmc3.ppu_read(0x0000);
+        mmc3.clock(85);
+        mmc3.ppu_read(0x1000);
+        mmc3.clock(28);
+    }
+
+    /// Counts the scanlines with an IRQ, acknowledging each one.
+    fn irqs(mmc3: &mut Mmc3, scanlines: usize) -> Vec<usize> {
+        let mut irqs = Vec::new();
+        for line in 0..scanlines {
+            scanline(mmc3);
+            if mmc3.irq() {
+                irqs.push(line);
+                mmc3.cpu_write(0xE000, 0).unwrap();
+                mmc3.cpu_write(0xE001, 0).unwrap();
+            }
+        }
+        irqs
+    }
+
+    #[test]
+    fn prg_rom_banking_modes() {
+        let mut mmc3 = mmc3(0);
+        for (register, bank) in [(6, 5), (7, 7)] {
+            mmc3.cpu_write(0x8000, register).unwrap();
+            mmc3.cpu_write(0x8001, bank).unwrap();
+        }
+        let pages =
+            |mmc3: &Mmc3| [0x8000, 0xA000, 0xC000, 0xE000].map(|addr| mmc3.cpu_read(addr).unwrap());
+        assert_eq!(pages(&mmc3), [5, 7, 30, 31]);
+
+        mmc3.cpu_write(0x8000, 0x40).unwrap();
+        assert_eq!(pages(&mmc3), [30, 7, 5, 31]);
+    }
+
+    #[test]
+    fn chr_banking_and_inversion() {
+        let mut mmc3 = mmc3(0);
+        for (register, bank) in [(0, 5), (1, 10), (2, 20), (3, 21), (4, 22), (5, 23)] {
+            mmc3.cpu_write(0x8000, register).unwrap();
+            mmc3.cpu_write(0x8001, bank).unwrap();
+        }
+        let pages = |mmc3: &mut Mmc3| {
+            (0..8)
+                .map(|slot| mmc3.ppu_read(slot * 0x400))
+                .collect::<Vec<_>>()
+        };
+        // the 2K banks ignore the low bit of the bank number
+        assert_eq!(pages(&mut mmc3), [4, 5, 10, 11, 20, 21, 22, 23]);
+
+        mmc3.cpu_write(0x8000, 0x80).unwrap();
+        assert_eq!(pages(&mut mmc3), [20, 21, 22, 23, 4, 5, 10, 11]);
+    }
+
+    #[test]
+    fn mirroring() {
+        let mut mmc3 = mmc3(0);
+        mmc3.cpu_write(0xA000, 0).unwrap();
+        assert_eq!(mmc3.mirroring(), Mirroring::Vertical);
+        mmc3.cpu_write(0xA000, 1).unwrap();
+        assert_eq!(mmc3.mirroring(), Mirroring::Horizontal);
+
+        let mut mmc3 = Mmc3::new(Rom {
+            mirroring: Mirroring::FourScreen,
+            ..rom(4, 0x40000, 0x40000)
+        });
+        mmc3.cpu_write(0xA000, 1).unwrap();
+        assert_eq!(mmc3.mirroring(), Mirroring::FourScreen);
+    }
+
+    #[test]
+    fn prg_ram_protection() {
+        let mut mmc3 = mmc3(0);
+        mmc3.cpu_write(0x6000, 0x42).unwrap();
+        mmc3.cpu_write(0xA001, 0xC0).unwrap();
+        assert!(mmc3.cpu_write(0x6000, 0x00).is_err());
+        assert_eq!(mmc3.cpu_read(0x6000).unwrap(), 0x42);
+
+        mmc3.cpu_write(0xA001, 0x00).unwrap();
+        assert!(mmc3.cpu_read(0x6000).is_err());
+    }
+
+    #[test]
+    fn irq_counts_scanlines_from_the_latch() {
+        let mut mmc3 = mmc3(0);
+        mmc3.cpu_write(0xC000, 2).unwrap();
+        mmc3.cpu_write(0xC001, 0).unwrap();
+        mmc3.cpu_write(0xE001, 0).unwrap();
+        assert_eq!(irqs(&mut mmc3, 9), [2, 5, 8]);
+
+        // disabling the IRQ also acknowledges it
+        for _ in 0..3 {
+            scanline(&mut mmc3);
+        }
+        assert!(mmc3.irq());
+        mmc3.cpu_write(0xE000, 0).unwrap();
+        assert!(!mmc3.irq());
+        assert_eq!(irqs(&mut mmc3, 9), []);
+    }
+
+    #[test]
+    fn short_drops_of_a12_are_filtered() {
+        let mut mmc3 = mmc3(0);
+        mmc3.cpu_write(0xC000, 0).unwrap();
+        mmc3.cpu_write(0xC001, 0).unwrap();
+        mmc3.cpu_write(0xE001, 0).unwrap();
+
+        mmc3.ppu_read(0x1000);
+        mmc3.ppu_read(0x0000);
+        mmc3.clock(A12_FILTER - 1);
+        mmc3.ppu_read(0x1000);
+        assert!(!mmc3.irq());
+
+        mmc3.ppu_read(0x0000);
+        mmc3.clock(A12_FILTER);
+        mmc3.ppu_read(0x1000);
+        assert!(mmc3.irq());
+    }
+
+    #[test]
+    fn sharp_fires_on_every_reload_of_a_zero_latch() {
+        let mut mmc3 = mmc3(0);
+        mmc3.cpu_write(0xC000, 0).unwrap();
+        mmc3.cpu_write(0xC001, 0).unwrap();
+        mmc3.cpu_write(0xE001, 0).unwrap();
+        assert_eq!(irqs(&mut mmc3, 4), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn nec_fires_once_for_a_zero_latch() {
+        let mut mmc3 = mmc3(SUBMAPPER_MMC3A);
+        mmc3.cpu_write(0xC000, 0).unwrap();
+        mmc3.cpu_write(0xC001, 0).unwrap();
+        mmc3.cpu_write(0xE001, 0).unwrap();
+        assert_eq!(irqs(&mut mmc3, 4), [0]);
+
+        // and again after the next reload request
+        mmc3.cpu_write(0xC001, 0).unwrap();
+        assert_eq!(irqs(&mut mmc3, 4), [0]);
+    }
+
+    #[test]
+    fn nec_and_sharp_agree_on_a_nonzero_latch() {
+        for submapper in [0, SUBMAPPER_MMC3A] {
+            let mut mmc3 = mmc3(submapper);
+            mmc3.cpu_write(0xC000, 1).unwrap();
+            mmc3.cpu_write(0xC001, 0).unwrap();
+            mmc3.cpu_write(0xE001, 0).unwrap();
+            assert_eq!(irqs(&mut mmc3, 6), [1, 3, 5]);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use super::Mapper;
+use crate::{
+    rom::{Mirroring, Rom},
+    Error,
+};
+
+/// Mapper 0, 16K or 32K of PRG ROM at $8000 and 8K of CHR ROM or RAM
+/// without any banking. A 16K ROM is mirrored at $C000.
+#[derive(Debug)]
+pub struct Nrom {
+    prg_rom: Vec<u8>,
+    chr: Vec<u8>,
+    chr_ram: bool,
+    mirroring: Mirroring,
+}
+
+impl Nrom {
+    pub fn new(rom: Rom) -> Self {
+        let chr_ram = rom.chr_rom.is_empty();
+        Self {
+            prg_rom: rom.prg_rom,
+            chr: if chr_ram {
+                vec![0; 0x2000]
+            } else {
+                rom.chr_rom
+            },
+            chr_ram,
+            mirroring: rom.mirroring,
+        }
+    }
+}
+
+impl Mapper for Nrom {
+    fn cpu_read(&self, addr: u16) -> Result<u8, Error> {
+        match addr {
+            0x8000..=0xFFFF => Ok(self.prg_rom[(addr - 0x8000) as usize % self.prg_rom.len()]),
+            _ => Err(Error::UnmappedRead { addr }),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> Result<(), Error> {
+        match addr {
+            0x8000..=0xFFFF => Err(Error::RomWrite { addr, value }),
+            _ => Err(Error::UnmappedWrite { addr, value }),
+        }
+    }
+
+    fn ppu_read(&mut self, addr: u16) -> u8 {
+        self.chr[addr as usize % self.chr.len()]
+    }
+
+    fn ppu_write(&mut self, addr: u16, value: u8) {
+        if self.chr_ram {
+            let len = self.chr.len();
+            self.chr[addr as usize % len] = value;
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+}
@@ -0,0 +1,138 @@
+use super::{bank_index, bus_conflict, Mapper, BUS_CONFLICTS};
+use crate::{
+    rom::{self, Mirroring, Rom},
+    Error,
+};
+
+const PRG_BANK_SIZE: usize = 0x4000;
+
+/// Mapper 2, a switchable 16K PRG ROM bank at $8000 and the last bank fixed
+/// at $C000, with 8K of CHR RAM.
+#[derive(Debug)]
+pub struct Uxrom {
+    prg_rom: Vec<u8>,
+    chr: Vec<u8>,
+    chr_ram: bool,
+    mirroring: Mirroring,
+    bus_conflicts: bool,
+    bank: u8,
+}
+
+impl Uxrom {
+    /// Fails when there is not even one PRG ROM bank to fix at $C000.
+    pub fn new(rom: Rom) -> Result<Self, rom::Error> {
+        if rom.prg_rom.len() < PRG_BANK_SIZE {
+            return Err(rom::Error::PrgRomTooSmall(rom.prg_rom.len()));
+        }
+
+        let chr_ram = rom.chr_rom.is_empty();
+        Ok(Self {
+            prg_rom: rom.prg_rom,
+            chr: if chr_ram {
+                vec![0; 0x2000]
+            } else {
+                rom.chr_rom
+            },
+            chr_ram,
+            mirroring: rom.mirroring,
+            bus_conflicts: rom.submapper == BUS_CONFLICTS,
+            bank: 0,
+        })
+    }
+}
+
+impl Mapper for Uxrom {
+    fn cpu_read(&self, addr: u16) -> Result<u8, Error> {
+        let len = self.prg_rom.len();
+        match addr {
+            0x8000..=0xBFFF => {
+                Ok(self.prg_rom[bank_index(len, PRG_BANK_SIZE, self.bank as usize, addr)])
+            }
+            0xC000..=0xFFFF => {
+                let last = len / PRG_BANK_SIZE - 1;
+                Ok(self.prg_rom[bank_index(len, PRG_BANK_SIZE, last, addr)])
+            }
+            _ => Err(Error::UnmappedRead { addr }),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> Result<(), Error> {
+        match addr {
+            0x8000..=0xFFFF => {
+                self.bank = bus_conflict(self.bus_conflicts, self.cpu_read(addr)?, value);
+                Ok(())
+            }
+            _ => Err(Error::UnmappedWrite { addr, value }),
+        }
+    }
+
+    fn ppu_read(&mut self, addr: u16) -> u8 {
+        self.chr[addr as usize % self.chr.len()]
+    }
+
+    fn ppu_write(&mut self, addr: u16, value: u8) {
+        if self.chr_ram {
+            let len = self.chr.len();
+            self.chr[addr as usize % len] = value;
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapper::tests::rom;
+
+    #[test]
+    fn switches_the_bank_at_8000() {
+        let mut uxrom = Uxrom::new(rom(2, 0x20000, 0)).unwrap();
+        assert_eq!(uxrom.cpu_read(0x8000).unwrap(), 0);
+        assert_eq!(uxrom.cpu_read(0xC000).unwrap(), 14);
+
+        uxrom.cpu_write(0xC000, 5).unwrap();
+        assert_eq!(uxrom.cpu_read(0x8000).unwrap(), 10);
+        assert_eq!(uxrom.cpu_read(0xBFFF).unwrap(), 11);
+        assert_eq!(uxrom.cpu_read(0xC000).unwrap(), 14);
+    }
+
+    #[test]
+    fn bus_conflicts_and_the_bank_number_with_rom() {
+        let mut uxrom = Uxrom::new(Rom {
+            submapper: BUS_CONFLICTS,
+            ..rom(2, 0x20000, 0)
+        })
+        .unwrap();
+        // ROM holds 0b1110 at $C000
+        uxrom.cpu_write(0xC000, 0b0101).unwrap();
+        assert_eq!(uxrom.cpu_read(0x8000).unwrap(), 8);
+    }
+
+    #[test]
+    fn chr_ram_and_mirroring() {
+        let mut uxrom = Uxrom::new(Rom {
+            mirroring: Mirroring::Vertical,
+            ..rom(2, 0x20000, 0)
+        })
+        .unwrap();
+        uxrom.ppu_write(0x1FFF, 0x42);
+        assert_eq!(uxrom.ppu_read(0x1FFF), 0x42);
+        assert_eq!(uxrom.mirroring(), Mirroring::Vertical);
+    }
+
+    #[test]
+    fn rejects_less_than_one_prg_bank() {
+        assert!(matches!(
+            Uxrom::new(rom(2, 0, 0)),
+            Err(rom::Error::PrgRomTooSmall(0))
+        ));
+        assert!(matches!(
+            Uxrom::new(rom(2, 0x2000, 0)),
+            Err(rom::Error::PrgRomTooSmall(0x2000))
+        ));
+        assert!(Uxrom::new(rom(2, 0x4000, 0)).is_ok());
+    }
+}
@@ -0,0 +1,158 @@
+use crate::rom::Mirroring;
+
+/// PPU dots per scanline, the prescaler subtracts 3 of them per CPU cycle.
+const DOTS_PER_SCANLINE: i16 = 341;
+
+/// The mirroring selected by the two bit field of the VRC4, VRC6 and VRC7.
+pub fn mirroring(bits: u8) -> Mirroring {
+    match bits & 0x03 {
+        0 => Mirroring::Vertical,
+        1 => Mirroring::Horizontal,
+        2 => Mirroring::SingleScreenLower,
+        _ => Mirroring::SingleScreenUpper,
+    }
+}
+
+/// The IRQ counter shared by the VRC4, VRC6 and VRC7. It counts up from the
+/// latch, either every CPU cycle or, through a prescaler, once per
+/// scanline's worth of cycles, and fires when it overflows.
+#[derive(Debug, Default)]
+pub struct VrcIrq {
+    pub latch: u8,
+    counter: u8,
+    prescaler: i16,
+    enabled: bool,
+    enable_after_ack: bool,
+    cycle_mode: bool,
+    pending: bool,
+}
+
+impl VrcIrq {
+    pub fn write_control(&mut self, value: u8) {
+        self.enable_after_ack = value & 0x01 != 0;
+        self.enabled = value & 0x02 != 0;
+        self.cycle_mode = value & 0x04 != 0;
+        self.pending = false;
+        if self.enabled {
+            self.counter = self.latch;
+            self.prescaler = DOTS_PER_SCANLINE;
+        }
+    }
+
+    pub fn acknowledge(&mut self) {
+        self.pending = false;
+        self.enabled = self.enable_after_ack;
+    }
+
+    pub fn pending(&self) -> bool {
+        self.pending
+    }
+
+    pub fn clock(&mut self, cycles: usize) {
+        if !self.enabled {
+            return;
+        }
+        for _ in 0..cycles {
+            if self.cycle_mode {
+                self.count();
+            } else {
+                self.prescaler -= 3;
+                if self.prescaler <= 0 {
+                    self.prescaler += DOTS_PER_SCANLINE;
+                    self.count();
+                }
+            }
+        }
+    }
+
+    fn count(&mut self) {
+        if self.counter == 0xFF {
+            self.counter = self.latch;
+            self.pending = true;
+        } else {
+            self.counter += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The cycles, counting from 1, on which the IRQ fires over `cycles`
+    /// cycles, acknowledging each one.
+    fn irqs(irq: &mut VrcIrq, cycles: usize) -> Vec<usize> {
+        let mut irqs = Vec::new();
+        for cycle in 1..=cycles {
+            irq.clock(1);
+            if irq.pending() {
+                irqs.push(cycle);
+                irq.acknowledge();
+            }
+        }
+        irqs
+    }
+
+    #[test]
+    fn mirroring_modes() {
+        assert_eq!(mirroring(0), Mirroring::Vertical);
+        assert_eq!(mirroring(1), Mirroring::Horizontal);
+        assert_eq!(mirroring(2), Mirroring::SingleScreenLower);
+        assert_eq!(mirroring(3), Mirroring::SingleScreenUpper);
+        assert_eq!(mirroring(0xFC), Mirroring::Vertical);
+    }
+
+    #[test]
+    fn cycle_mode_counts_every_cycle_from_the_latch() {
+        let mut irq = VrcIrq {
+            latch: 0xFD,
+            ..VrcIrq::default()
+        };
+        // enabled, staying enabled after an acknowledge, cycle mode
+        irq.write_control(0x07);
+        assert_eq!(irqs(&mut irq, 9), [3, 6, 9]);
+    }
+
+    #[test]
+    fn scanline_mode_counts_every_341_dots() {
+        let mut irq = VrcIrq {
+            latch: 0xFF,
+            ..VrcIrq::default()
+        };
+        irq.write_control(0x03);
+        // 113 2/3 cycles per scanline
+        assert_eq!(irqs(&mut irq, 341 * 2), [114, 228, 341, 455, 569, 682]);
+    }
+
+    #[test]
+    fn acknowledge_restores_the_enable_after_ack_bit() {
+        let mut irq = VrcIrq {
+            latch: 0xFF,
+            ..VrcIrq::default()
+        };
+        irq.write_control(0x06);
+        assert_eq!(irqs(&mut irq, 4), [1]);
+
+        irq.write_control(0x00);
+        irq.clock(4);
+        assert!(!irq.pending());
+    }
+
+    #[test]
+    fn writing_the_control_acknowledges_and_reloads() {
+        let mut irq = VrcIrq {
+            latch: 0xFE,
+            ..VrcIrq::default()
+        };
+        irq.write_control(0x06);
+        irq.clock(2);
+        assert!(irq.pending());
+
+        irq.write_control(0x06);
+        assert!(!irq.pending());
+        irq.clock(1);
+        assert!(!irq.pending());
+        irq.clock(1);
+        assert!(irq.pending());
+    }
+}
@@ -0,0 +1,321 @@
+use super::{
+    bank_index,
+    vrc::{self, VrcIrq},
+    Mapper,
+};
+use crate::{
+    rom::{Mirroring, Rom},
+    Error,
+};
+
+const PRG_BANK_SIZE: usize = 0x2000;
+const CHR_BANK_SIZE: usize = 0x0400;
+
+/// Mappers 21, 22, 23 and 25, the Konami VRC4 and the VRC2 it extends. Has
+/// two switchable 8K PRG ROM banks and eight 1K CHR banks. The VRC4 adds
+/// single-screen mirroring, a PRG swap mode and the VRC IRQ counter.
+///
+/// The boards wire different CPU address lines to the chip's register
+/// select inputs, which the mapper and submapper numbers tell apart. With
+/// submapper 0 the lines of every variant of the mapper are combined.
+#[derive(Debug)]
+pub struct Vrc4 {
+    prg_rom: Vec<u8>,
+    prg_ram: Vec<u8>,
+    chr: Vec<u8>,
+    chr_ram: bool,
+    vrc2: bool,
+    /// VRC2a only connects the upper CHR bank bits.
+    chr_shift: bool,
+    /// The address lines connected to register select inputs 0 and 1.
+    a0: u16,
+    a1: u16,
+    prg_banks: [u8; 2],
+    chr_banks: [u16; 8],
+    mirroring: u8,
+    prg_swap: bool,
+    irq: VrcIrq,
+}
+
+impl Vrc4 {
+    pub fn new(rom: Rom) -> Self {
+        // (VRC2, A0, A1)
+        let (vrc2, a0, a1) = match (rom.mapper, rom.submapper) {
+            // VRC4a and VRC4c
+            (21, 1) => (false, 0x02, 0x04),
+            (21, 2) => (false, 0x40, 0x80),
+            (21, _) => (false, 0x42, 0x84),
+            // VRC2a
+            (22, _) => (true, 0x02, 0x01),
+            // VRC4f, VRC4e and VRC2b
+            (23, 1) => (false, 0x01, 0x02),
+            (23, 2) => (false, 0x04, 0x08),
+            (23, 3) => (true, 0x01, 0x02),
+            (23, _) => (false, 0x05, 0x0A),
+            // VRC4b, VRC4d and VRC2c
+            (25, 1) => (false, 0x02, 0x01),
+            (25, 2) => (false, 0x08, 0x04),
+            (25, 3) => (true, 0x02, 0x01),
+            (_, _) => (false, 0x0A, 0x05),
+        };
+
+        let chr_ram = rom.chr_rom.is_empty();
+        Self {
+            prg_rom: rom.prg_rom,
+            prg_ram: vec![0; rom.prg_ram_size],
+            chr: if chr_ram {
+                vec![0; 0x2000]
+            } else {
+                rom.chr_rom
+            },
+            chr_ram,
+            vrc2,
+            chr_shift: rom.mapper == 22,
+            a0,
+            a1,
+            prg_banks: [0; 2],
+            chr_banks: [0; 8],
+            mirroring: 0,
+            prg_swap: false,
+            irq: VrcIrq::default(),
+        }
+    }
+
+    /// The register `addr` selects, as $x000-$x003.
+    fn register(&self, addr: u16) -> u16 {
+        let a0 = (addr & self.a0 != 0) as u16;
+        let a1 = (addr & self.a1 != 0) as u16;
+        (addr & 0xF000) | a1 << 1 | a0
+    }
+
+    fn prg_rom_index(&self, addr: u16) -> usize {
+        let second_last = (self.prg_rom.len() / PRG_BANK_SIZE).saturating_sub(2);
+        let bank = match addr {
+            0x8000..=0x9FFF if self.prg_swap => second_last,
+            0x8000..=0x9FFF => self.prg_banks[0] as usize,
+            0xA000..=0xBFFF => self.prg_banks[1] as usize,
+            0xC000..=0xDFFF if self.prg_swap => self.prg_banks[0] as usize,
+            0xC000..=0xDFFF => second_last,
+            _ => second_last + 1,
+        };
+        bank_index(self.prg_rom.len(), PRG_BANK_SIZE, bank, addr)
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        let bank = self.chr_banks[(addr >> 10) as usize & 0x07];
+        let bank = if self.chr_shift { bank >> 1 } else { bank };
+        bank_index(self.chr.len(), CHR_BANK_SIZE, bank as usize, addr)
+    }
+
+    fn write_register(&mut self, addr: u16, value: u8) {
+        match self.register(addr) {
+            0x8000..=0x8003 => self.prg_banks[0] = value & 0x1F,
+            0x9000..=0x9003 if self.vrc2 => self.mirroring = value & 0x01,
+            0x9000..=0x9001 => self.mirroring = value & 0x03,
+            0x9002..=0x9003 => self.prg_swap = value & 0x02 != 0,
+            0xA000..=0xA003 => self.prg_banks[1] = value & 0x1F,
+            register @ 0xB000..=0xE003 => {
+                // each bank number is written a nibble at a time, the low
+                // one to the even register of a pair
+                let slot = ((register >> 12) - 0xB) as usize * 2 + (register as usize & 0x02) / 2;
+                let bank = &mut self.chr_banks[slot];
+                if register & 0x01 == 0 {
+                    *bank = (*bank & 0x1F0) | (value & 0x0F) as u16;
+                } else {
+                    let high = if self.vrc2 { 0x0F } else { 0x1F };
+                    *bank = (*bank & 0x0F) | ((value & high) as u16) << 4;
+                }
+            }
+            0xF000 if !self.vrc2 => self.irq.latch = (self.irq.latch & 0xF0) | (value & 0x0F),
+            0xF001 if !self.vrc2 => self.irq.latch = (self.irq.latch & 0x0F) | (value << 4),
+            0xF002 if !self.vrc2 => self.irq.write_control(value),
+            0xF003 if !self.vrc2 => self.irq.acknowledge(),
+            _ => {}
+        }
+    }
+}
+
+impl Mapper for Vrc4 {
+    fn cpu_read(&self, addr: u16) -> Result<u8, Error> {
+        match addr {
+            0x6000..=0x7FFF if !self.prg_ram.is_empty() => {
+                Ok(self.prg_ram[(addr - 0x6000) as usize % self.prg_ram.len()])
+            }
+            0x8000..=0xFFFF => Ok(self.prg_rom[self.prg_rom_index(addr)]),
+            _ => Err(Error::UnmappedRead { addr }),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> Result<(), Error> {
+        match addr {
+            0x6000..=0x7FFF if !self.prg_ram.is_empty() => {
+                let len = self.prg_ram.len();
+                self.prg_ram[(addr - 0x6000) as usize % len] = value;
+                Ok(())
+            }
+            0x8000..=0xFFFF => {
+                self.write_register(addr, value);
+                Ok(())
+            }
+            _ => Err(Error::UnmappedWrite { addr, value }),
+        }
+    }
+
+    fn ppu_read(&mut self, addr: u16) -> u8 {
+        self.chr[self.chr_index(addr)]
+    }
+
+    fn ppu_write(&mut self, addr: u16, value: u8) {
+        if self.chr_ram {
+            let index = self.chr_index(addr);
+            self.chr[index] = value;
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        vrc::mirroring(self.mirroring)
+    }
+
+    fn irq(&self) -> bool {
+        self.irq.pending()
+    }
+
+    fn clock(&mut self, cycles: usize) {
+        self.irq.clock(cycles);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapper::tests::rom;
+
+    fn board(mapper: u16, submapper: u8) -> Vrc4 {
+        Vrc4::new(Rom {
+            submapper,
+            prg_ram_size: 0x2000,
+            ..rom(mapper, 0x40000, 0x40000)
+        })
+    }
+
+    /// The 1K CHR page at $0400 after loading bank $15 through the register
+    /// pair selected with `a0` and `a1`.
+    fn chr_bank_1(mapper: u16, submapper: u8, a0: u16, a1: u16) -> u8 {
+        let mut vrc4 = board(mapper, submapper);
+        vrc4.cpu_write(0xB000 | a1, 0x05).unwrap();
+        vrc4.cpu_write(0xB000 | a1 | a0, 0x01).unwrap();
+        vrc4.ppu_read(0x0400)
+    }
+
+    #[test]
+    fn register_select_lines_of_each_board() {
+        for (mapper, submapper, a0, a1) in [
+            // VRC4a, VRC4c and both
+            (21, 1, 0x02, 0x04),
+            (21, 2, 0x40, 0x80),
+            (21, 0, 0x02, 0x04),
+            (21, 0, 0x40, 0x80),
+            // VRC4f, VRC4e and both
+            (23, 1, 0x01, 0x02),
+            (23, 2, 0x04, 0x08),
+            (23, 0, 0x01, 0x02),
+            (23, 0, 0x04, 0x08),
+            // VRC4b, VRC4d and both
+            (25, 1, 0x02, 0x01),
+            (25, 2, 0x08, 0x04),
+            (25, 0, 0x02, 0x01),
+            (25, 0, 0x08, 0x04),
+            // VRC2b and VRC2c
+            (23, 3, 0x01, 0x02),
+            (25, 3, 0x02, 0x01),
+        ] {
+            assert_eq!(
+                chr_bank_1(mapper, submapper, a0, a1),
+                0x15,
+                "mapper {} submapper {} with A0 {:#04x} and A1 {:#04x}",
+                mapper,
+                submapper,
+                a0,
+                a1
+            );
+        }
+    }
+
+    #[test]
+    fn vrc2a_ignores_the_low_chr_bank_bit() {
+        assert_eq!(chr_bank_1(22, 0, 0x02, 0x01), 0x0A);
+    }
+
+    #[test]
+    fn vrc2_chr_banks_have_one_bit_less() {
+        let mut vrc2 = board(23, 3);
+        vrc2.cpu_write(0xB001, 0x1F).unwrap();
+        assert_eq!(vrc2.chr_banks[0], 0x0F0);
+
+        let mut vrc4 = board(23, 1);
+        vrc4.cpu_write(0xB001, 0x1F).unwrap();
+        assert_eq!(vrc4.chr_banks[0], 0x1F0);
+    }
+
+    #[test]
+    fn prg_rom_swap_mode() {
+        let mut vrc4 = board(21, 1);
+        vrc4.cpu_write(0x8000, 5).unwrap();
+        vrc4.cpu_write(0xA000, 7).unwrap();
+        let pages =
+            |vrc4: &Vrc4| [0x8000, 0xA000, 0xC000, 0xE000].map(|addr| vrc4.cpu_read(addr).unwrap());
+        assert_eq!(pages(&vrc4), [5, 7, 30, 31]);
+
+        // $9002 on VRC4a
+        vrc4.cpu_write(0x9004, 0x02).unwrap();
+        assert_eq!(pages(&vrc4), [30, 7, 5, 31]);
+    }
+
+    #[test]
+    fn mirroring() {
+        let mut vrc4 = board(21, 1);
+        for (value, mirroring) in [
+            (0, Mirroring::Vertical),
+            (1, Mirroring::Horizontal),
+            (2, Mirroring::SingleScreenLower),
+            (3, Mirroring::SingleScreenUpper),
+        ] {
+            vrc4.cpu_write(0x9000, value).unwrap();
+            assert_eq!(vrc4.mirroring(), mirroring);
+        }
+
+        // the VRC2 only has the first two, at any of $9000-$9003
+        let mut vrc2 = board(22, 0);
+        vrc2.cpu_write(0x9003, 1).unwrap();
+        assert_eq!(vrc2.mirroring(), Mirroring::Horizontal);
+        vrc2.cpu_write(0x9001, 2).unwrap();
+        assert_eq!(vrc2.mirroring(), Mirroring::Vertical);
+    }
+
+    #[test]
+    fn irq() {
+        let mut vrc4 = board(21, 1);
+        // the latch nibbles at $F000 and $F001, then the control at $F002
+        vrc4.cpu_write(0xF000, 0x0D).unwrap();
+        vrc4.cpu_write(0xF002, 0x0F).unwrap();
+        vrc4.cpu_write(0xF004, 0x06).unwrap();
+        vrc4.clock(2);
+        assert!(!vrc4.irq());
+        vrc4.clock(1);
+        assert!(vrc4.irq());
+
+        // acknowledged through $F003
+        vrc4.cpu_write(0xF006, 0).unwrap();
+        assert!(!vrc4.irq());
+    }
+
+    #[test]
+    fn vrc2_has_no_irq() {
+        let mut vrc2 = board(23, 3);
+        vrc2.cpu_write(0xF000, 0x0F).unwrap();
+        vrc2.cpu_write(0xF001, 0x0F).unwrap();
+        vrc2.cpu_write(0xF002, 0x06).unwrap();
+        vrc2.clock(10);
+        assert!(!vrc2.irq());
+    }
+}
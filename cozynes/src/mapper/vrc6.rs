@@ -0,0 +1,275 @@
+use super::{
+    bank_index,
+    vrc::{self, VrcIrq},
+    Mapper,
+};
+use crate::{
+    rom::{self, Mirroring, Rom},
+    Error,
+};
+
+const PRG_16K_BANK_SIZE: usize = 0x4000;
+const PRG_8K_BANK_SIZE: usize = 0x2000;
+const CHR_BANK_SIZE: usize = 0x0400;
+
+/// Mappers 24 and 26, the Konami VRC6. Has a switchable 16K and 8K PRG ROM
+/// bank, eight CHR bank registers and the VRC IRQ counter. Mapper 26 swaps
+/// the A0 and A1 register select lines. The expansion audio is not
+/// emulated.
+#[derive(Debug)]
+pub struct Vrc6 {
+    prg_rom: Vec<u8>,
+    prg_ram: Vec<u8>,
+    chr: Vec<u8>,
+    chr_ram: bool,
+    swapped_lines: bool,
+    prg_16k: u8,
+    prg_8k: u8,
+    chr_banks: [u8; 8],
+    /// $B003, the CHR banking mode, mirroring and PRG RAM enable.
+    ppu_mode: u8,
+    irq: VrcIrq,
+}
+
+impl Vrc6 {
+    /// Fails when there is not even one PRG ROM bank to fix at $E000.
+    pub fn new(rom: Rom) -> Result<Self, rom::Error> {
+        if rom.prg_rom.len() < PRG_8K_BANK_SIZE {
+            return Err(rom::Error::PrgRomTooSmall(rom.prg_rom.len()));
+        }
+
+        let chr_ram = rom.chr_rom.is_empty();
+        Ok(Self {
+            prg_rom: rom.prg_rom,
+            prg_ram: vec![0; rom.prg_ram_size],
+            chr: if chr_ram {
+                vec![0; 0x2000]
+            } else {
+                rom.chr_rom
+            },
+            chr_ram,
+            swapped_lines: rom.mapper == 26,
+            prg_16k: 0,
+            prg_8k: 0,
+            chr_banks: [0; 8],
+            ppu_mode: 0,
+            irq: VrcIrq::default(),
+        })
+    }
+
+    /// The register `addr` selects, as $x000-$x003.
+    fn register(&self, addr: u16) -> u16 {
+        if self.swapped_lines {
+            (addr & 0xF000) | (addr & 0x01) << 1 | (addr & 0x02) >> 1
+        } else {
+            addr & 0xF003
+        }
+    }
+
+    fn prg_ram_enabled(&self) -> bool {
+        !self.prg_ram.is_empty() && self.ppu_mode & 0x80 != 0
+    }
+
+    fn prg_rom_index(&self, addr: u16) -> usize {
+        let len = self.prg_rom.len();
+        match addr {
+            0x8000..=0xBFFF => bank_index(len, PRG_16K_BANK_SIZE, self.prg_16k as usize, addr),
+            0xC000..=0xDFFF => bank_index(len, PRG_8K_BANK_SIZE, self.prg_8k as usize, addr),
+            _ => bank_index(len, PRG_8K_BANK_SIZE, len / PRG_8K_BANK_SIZE - 1, addr),
+        }
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        let slot = (addr >> 10) as usize & 0x07;
+        // 2K banks take their low bit from PPU A10
+        let two_k = |register: u8| (register & !1) | (slot & 0x01) as u8;
+        let bank = match (self.ppu_mode & 0x03, slot) {
+            (0, _) => self.chr_banks[slot],
+            (1, _) => two_k(self.chr_banks[slot / 2]),
+            (_, 0..=3) => self.chr_banks[slot],
+            (_, _) => two_k(self.chr_banks[4 + (slot - 4) / 2]),
+        };
+        bank_index(self.chr.len(), CHR_BANK_SIZE, bank as usize, addr)
+    }
+
+    fn write_register(&mut self, addr: u16, value: u8) {
+        match self.register(addr) {
+            0x8000..=0x8003 => self.prg_16k = value & 0x0F,
+            0xB003 => self.ppu_mode = value,
+            0xC000..=0xC003 => self.prg_8k = value & 0x1F,
+            register @ (0xD000..=0xD003 | 0xE000..=0xE003) => {
+                let slot = (register >> 12) as usize - 0xD;
+                self.chr_banks[slot * 4 + (register & 0x03) as usize] = value;
+            }
+            0xF000 => self.irq.latch = value,
+            0xF001 => self.irq.write_control(value),
+            0xF002 => self.irq.acknowledge(),
+            // expansion audio
+            _ => {}
+        }
+    }
+}
+
+impl Mapper for Vrc6 {
+    fn cpu_read(&self, addr: u16) -> Result<u8, Error> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => {
+                Ok(self.prg_ram[(addr - 0x6000) as usize % self.prg_ram.len()])
+            }
+            0x8000..=0xFFFF => Ok(self.prg_rom[self.prg_rom_index(addr)]),
+            _ => Err(Error::UnmappedRead { addr }),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> Result<(), Error> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => {
+                let len = self.prg_ram.len();
+                self.prg_ram[(addr - 0x6000) as usize % len] = value;
+                Ok(())
+            }
+            0x8000..=0xFFFF => {
+                self.write_register(addr, value);
+                Ok(())
+            }
+            _ => Err(Error::UnmappedWrite { addr, value }),
+        }
+    }
+
+    fn ppu_read(&mut self, addr: u16) -> u8 {
+        self.chr[self.chr_index(addr)]
+    }
+
+    fn ppu_write(&mut self, addr: u16, value: u8) {
+        if self.chr_ram {
+            let index = self.chr_index(addr);
+            self.chr[index] = value;
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        vrc::mirroring(self.ppu_mode >> 2)
+    }
+
+    fn irq(&self) -> bool {
+        self.irq.pending()
+    }
+
+    fn clock(&mut self, cycles: usize) {
+        self.irq.clock(cycles);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapper::tests::rom;
+
+    fn board(mapper: u16) -> Vrc6 {
+        Vrc6::new(Rom {
+            prg_ram_size: 0x2000,
+            ..rom(mapper, 0x40000, 0x40000)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn vrc6b_swaps_a0_and_a1() {
+        let mut vrc6a = board(24);
+        vrc6a.cpu_write(0xD001, 5).unwrap();
+        vrc6a.cpu_write(0xD002, 6).unwrap();
+        assert_eq!([vrc6a.ppu_read(0x0400), vrc6a.ppu_read(0x0800)], [5, 6]);
+
+        let mut vrc6b = board(26);
+        vrc6b.cpu_write(0xD001, 5).unwrap();
+        vrc6b.cpu_write(0xD002, 6).unwrap();
+        assert_eq!([vrc6b.ppu_read(0x0400), vrc6b.ppu_read(0x0800)], [6, 5]);
+    }
+
+    #[test]
+    fn prg_rom_banks() {
+        let mut vrc6 = board(24);
+        vrc6.cpu_write(0x8000, 3).unwrap();
+        vrc6.cpu_write(0xC000, 9).unwrap();
+        let pages = [0x8000, 0xA000, 0xC000, 0xE000].map(|addr| vrc6.cpu_read(addr).unwrap());
+        assert_eq!(pages, [6, 7, 9, 31]);
+    }
+
+    #[test]
+    fn chr_banking_modes() {
+        let mut vrc6 = board(24);
+        for (i, addr) in [
+            0xD000, 0xD001, 0xD002, 0xD003, 0xE000, 0xE001, 0xE002, 0xE003,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            vrc6.cpu_write(addr, 0x10 + i as u8 * 2).unwrap();
+        }
+        let pages = |vrc6: &mut Vrc6| {
+            (0..8)
+                .map(|slot| vrc6.ppu_read(slot * 0x400))
+                .collect::<Vec<_>>()
+        };
+        // eight 1K banks
+        vrc6.cpu_write(0xB003, 0x00).unwrap();
+        assert_eq!(
+            pages(&mut vrc6),
+            [0x10, 0x12, 0x14, 0x16, 0x18, 0x1A, 0x1C, 0x1E]
+        );
+        // four 2K banks from the first four registers
+        vrc6.cpu_write(0xB003, 0x01).unwrap();
+        assert_eq!(
+            pages(&mut vrc6),
+            [0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17]
+        );
+        // four 1K banks, then two 2K banks from $E000 and $E001
+        vrc6.cpu_write(0xB003, 0x02).unwrap();
+        assert_eq!(
+            pages(&mut vrc6),
+            [0x10, 0x12, 0x14, 0x16, 0x18, 0x19, 0x1A, 0x1B]
+        );
+    }
+
+    #[test]
+    fn mirroring_and_prg_ram() {
+        let mut vrc6 = board(26);
+        for (value, mirroring) in [
+            (0x00, Mirroring::Vertical),
+            (0x04, Mirroring::Horizontal),
+            (0x08, Mirroring::SingleScreenLower),
+            (0x0C, Mirroring::SingleScreenUpper),
+        ] {
+            vrc6.cpu_write(0xB003, value).unwrap();
+            assert_eq!(vrc6.mirroring(), mirroring);
+        }
+
+        assert!(vrc6.cpu_read(0x6000).is_err());
+        vrc6.cpu_write(0xB003, 0x80).unwrap();
+        vrc6.cpu_write(0x6000, 0x42).unwrap();
+        assert_eq!(vrc6.cpu_read(0x6000).unwrap(), 0x42);
+    }
+
+    #[test]
+    fn irq_registers() {
+        for (mapper, control, acknowledge) in [(24, 0xF001, 0xF002), (26, 0xF002, 0xF001)] {
+            let mut vrc6 = board(mapper);
+            vrc6.cpu_write(0xF000, 0xFD).unwrap();
+            vrc6.cpu_write(control, 0x06).unwrap();
+            vrc6.clock(3);
+            assert!(vrc6.irq(), "mapper {}", mapper);
+
+            vrc6.cpu_write(acknowledge, 0).unwrap();
+            assert!(!vrc6.irq(), "mapper {}", mapper);
+        }
+    }
+
+    #[test]
+    fn rejects_less_than_one_prg_bank() {
+        assert!(matches!(
+            Vrc6::new(rom(24, 0, 0)),
+            Err(rom::Error::PrgRomTooSmall(0))
+        ));
+        assert!(Vrc6::new(rom(24, 0x2000, 0)).is_ok());
+    }
+}
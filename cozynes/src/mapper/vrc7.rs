@@ -0,0 +1,239 @@
+use super::{
+    bank_index,
+    vrc::{self, VrcIrq},
+    Mapper,
+};
+use crate::{
+    rom::{self, Mirroring, Rom},
+    Error,
+};
+
+const PRG_BANK_SIZE: usize = 0x2000;
+const CHR_BANK_SIZE: usize = 0x0400;
+
+/// Mapper 85, the Konami VRC7. Has three switchable 8K PRG ROM banks, eight
+/// 1K CHR banks and the VRC IRQ counter. VRC7a selects the second register
+/// of each pair with A4 and VRC7b with A3, submappers 2 and 1. The FM
+/// expansion audio is not emulated.
+#[derive(Debug)]
+pub struct Vrc7 {
+    prg_rom: Vec<u8>,
+    prg_ram: Vec<u8>,
+    chr: Vec<u8>,
+    chr_ram: bool,
+    /// The address lines that select the second register of a pair.
+    a0: u16,
+    prg_banks: [u8; 3],
+    chr_banks: [u8; 8],
+    /// $E000, the mirroring and PRG RAM enable.
+    control: u8,
+    irq: VrcIrq,
+}
+
+impl Vrc7 {
+    /// Fails when there is not even one PRG ROM bank to fix at $E000.
+    pub fn new(rom: Rom) -> Result<Self, rom::Error> {
+        if rom.prg_rom.len() < PRG_BANK_SIZE {
+            return Err(rom::Error::PrgRomTooSmall(rom.prg_rom.len()));
+        }
+
+        let a0 = match rom.submapper {
+            1 => 0x08,
+            2 => 0x10,
+            _ => 0x18,
+        };
+
+        let chr_ram = rom.chr_rom.is_empty();
+        Ok(Self {
+            prg_rom: rom.prg_rom,
+            prg_ram: vec![0; rom.prg_ram_size],
+            chr: if chr_ram {
+                vec![0; 0x2000]
+            } else {
+                rom.chr_rom
+            },
+            chr_ram,
+            a0,
+            prg_banks: [0; 3],
+            chr_banks: [0; 8],
+            control: 0,
+            irq: VrcIrq::default(),
+        })
+    }
+
+    /// The register `addr` selects, as $x000 or $x001.
+    fn register(&self, addr: u16) -> u16 {
+        (addr & 0xF000) | (addr & self.a0 != 0) as u16
+    }
+
+    fn prg_ram_enabled(&self) -> bool {
+        !self.prg_ram.is_empty() && self.control & 0x80 != 0
+    }
+
+    fn prg_rom_index(&self, addr: u16) -> usize {
+        let bank = match addr {
+            0x8000..=0x9FFF => self.prg_banks[0] as usize,
+            0xA000..=0xBFFF => self.prg_banks[1] as usize,
+            0xC000..=0xDFFF => self.prg_banks[2] as usize,
+            _ => self.prg_rom.len() / PRG_BANK_SIZE - 1,
+        };
+        bank_index(self.prg_rom.len(), PRG_BANK_SIZE, bank, addr)
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        let bank = self.chr_banks[(addr >> 10) as usize & 0x07];
+        bank_index(self.chr.len(), CHR_BANK_SIZE, bank as usize, addr)
+    }
+
+    fn write_register(&mut self, addr: u16, value: u8) {
+        match self.register(addr) {
+            0x8000 => self.prg_banks[0] = value & 0x3F,
+            0x8001 => self.prg_banks[1] = value & 0x3F,
+            0x9000 => self.prg_banks[2] = value & 0x3F,
+            register @ 0xA000..=0xD001 => {
+                let slot = ((register >> 12) - 0xA) as usize * 2 + (register & 0x01) as usize;
+                self.chr_banks[slot] = value;
+            }
+            0xE000 => self.control = value,
+            0xE001 => self.irq.latch = value,
+            0xF000 => self.irq.write_control(value),
+            0xF001 => self.irq.acknowledge(),
+            // expansion audio
+            _ => {}
+        }
+    }
+}
+
+impl Mapper for Vrc7 {
+    fn cpu_read(&self, addr: u16) -> Result<u8, Error> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => {
+                Ok(self.prg_ram[(addr - 0x6000) as usize % self.prg_ram.len()])
+            }
+            0x8000..=0xFFFF => Ok(self.prg_rom[self.prg_rom_index(addr)]),
+            _ => Err(Error::UnmappedRead { addr }),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> Result<(), Error> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => {
+                let len = self.prg_ram.len();
+                self.prg_ram[(addr - 0x6000) as usize % len] = value;
+                Ok(())
+            }
+            0x8000..=0xFFFF => {
+                self.write_register(addr, value);
+                Ok(())
+            }
+            _ => Err(Error::UnmappedWrite { addr, value }),
+        }
+    }
+
+    fn ppu_read(&mut self, addr: u16) -> u8 {
+        self.chr[self.chr_index(addr)]
+    }
+
+    fn ppu_write(&mut self, addr: u16, value: u8) {
+        if self.chr_ram {
+            let index = self.chr_index(addr);
+            self.chr[index] = value;
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        vrc::mirroring(self.control)
+    }
+
+    fn irq(&self) -> bool {
+        self.irq.pending()
+    }
+
+    fn clock(&mut self, cycles: usize) {
+        self.irq.clock(cycles);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapper::tests::rom;
+
+    fn board(submapper: u8) -> Vrc7 {
+        Vrc7::new(Rom {
+            submapper,
+            prg_ram_size: 0x2000,
+            ..rom(85, 0x40000, 0x40000)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn register_select_lines_of_each_board() {
+        // VRC7b, VRC7a and both
+        for (submapper, a0) in [(1, 0x08), (2, 0x10), (0, 0x08), (0, 0x10)] {
+            let mut vrc7 = board(submapper);
+            vrc7.cpu_write(0x8000, 3).unwrap();
+            vrc7.cpu_write(0x8000 | a0, 5).unwrap();
+            vrc7.cpu_write(0xA000 | a0, 9).unwrap();
+            assert_eq!(vrc7.cpu_read(0x8000).unwrap(), 3);
+            assert_eq!(vrc7.cpu_read(0xA000).unwrap(), 5);
+            assert_eq!(vrc7.ppu_read(0x0400), 9);
+        }
+
+        // A4 is not connected on VRC7b
+        let mut vrc7 = board(1);
+        vrc7.cpu_write(0x8010, 5).unwrap();
+        assert_eq!(vrc7.cpu_read(0x8000).unwrap(), 5);
+    }
+
+    #[test]
+    fn prg_rom_banks() {
+        let mut vrc7 = board(1);
+        vrc7.cpu_write(0x8000, 1).unwrap();
+        vrc7.cpu_write(0x8008, 2).unwrap();
+        vrc7.cpu_write(0x9000, 3).unwrap();
+        let pages = [0x8000, 0xA000, 0xC000, 0xE000].map(|addr| vrc7.cpu_read(addr).unwrap());
+        assert_eq!(pages, [1, 2, 3, 31]);
+    }
+
+    #[test]
+    fn mirroring_and_prg_ram() {
+        let mut vrc7 = board(1);
+        for (value, mirroring) in [
+            (0, Mirroring::Vertical),
+            (1, Mirroring::Horizontal),
+            (2, Mirroring::SingleScreenLower),
+            (3, Mirroring::SingleScreenUpper),
+        ] {
+            vrc7.cpu_write(0xE000, value).unwrap();
+            assert_eq!(vrc7.mirroring(), mirroring);
+        }
+
+        assert!(vrc7.cpu_read(0x6000).is_err());
+        vrc7.cpu_write(0xE000, 0x80).unwrap();
+        vrc7.cpu_write(0x6000, 0x42).unwrap();
+        assert_eq!(vrc7.cpu_read(0x6000).unwrap(), 0x42);
+    }
+
+    #[test]
+    fn irq_registers() {
+        let mut vrc7 = board(2);
+        vrc7.cpu_write(0xE010, 0xFD).unwrap();
+        vrc7.cpu_write(0xF000, 0x06).unwrap();
+        vrc7.clock(3);
+        assert!(vrc7.irq());
+
+        vrc7.cpu_write(0xF010, 0).unwrap();
+        assert!(!vrc7.irq());
+    }
+
+    #[test]
+    fn rejects_less_than_one_prg_bank() {
+        assert!(matches!(
+            Vrc7::new(rom(85, 0, 0)),
+            Err(rom::Error::PrgRomTooSmall(0))
+        ));
+        assert!(Vrc7::new(rom(85, 0x2000, 0)).is_ok());
+    }
+}
@@ -1,10 +1,40 @@
+use crate::Error;
+
 pub trait Mem {
     fn read_byte(&self, addr: u16) -> u8;
     fn write_byte(&mut self, addr: u16, value: u8);
 
+    /// Drains the faults recorded by accesses since the last call.
+    fn take_faults(&mut self) -> Vec<Error> {
+        Vec::new()
+    }
+
+    /// Whether a device on the bus, such as a cartridge, asserts the IRQ
+    /// line. Polled by the CPU before every instruction.
+    fn irq(&self) -> bool {
+        false
+    }
+
+    /// Called after the CPU spent `cycles` cycles, for devices that keep
+    /// time with the CPU clock. `Cpu::tick` clocks one cycle at a time,
+    /// `Cpu::step` a whole instruction.
+    fn clock(&mut self, _cycles: usize) {}
+
     fn read_word(&self, addr: u16) -> u16 {
         let lo = self.read_byte(addr) as u16;
-        let hi = self.read_byte(addr + 1) as u16;
+        let hi = self.read_byte(addr.wrapping_add(1)) as u16;
+        (hi << 8) | lo
+    }
+
+    /// Reads `addr` without side effects, for crash dumps and debuggers.
+    /// Buses whose reads can have side effects or record faults override it.
+    fn peek_byte(&self, addr: u16) -> u8 {
+        self.read_byte(addr)
+    }
+
+    fn peek_word(&self, addr: u16) -> u16 {
+        let lo = self.peek_byte(addr) as u16;
+        let hi = self.peek_byte(addr.wrapping_add(1)) as u16;
         (hi << 8) | lo
     }
 
@@ -15,3 +45,41 @@ pub trait Mem {
         self.write_byte(addr.wrapping_add(1), hi);
     }
 }
+
+/// 64K of RAM without any memory mapped hardware, for running plain 6502
+/// programs such as test suites.
+#[derive(Debug, Clone)]
+pub struct Ram {
+    pub data: Vec<u8>,
+}
+
+impl Ram {
+    pub fn new() -> Self {
+        Self {
+            data: vec![0; 0x10000],
+        }
+    }
+
+    /// Copies `bytes` to `origin`, wrapping around at the end of memory.
+    pub fn load(&mut self, origin: u16, bytes: &[u8]) {
+        for (i, &byte) in bytes.iter().enumerate() {
+            self.data[origin.wrapping_add(i as u16) as usize] = byte;
+        }
+    }
+}
+
+impl Default for Ram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Mem for Ram {
+    fn read_byte(&self, addr: u16) -> u8 {
+        self.data[addr as usize]
+    }
+
+    fn write_byte(&mut self, addr: u16, value: u8) {
+        self.data[addr as usize] = value;
+    }
+}
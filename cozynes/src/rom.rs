@@ -1,16 +1,24 @@
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Mirroring {
     Vertical,
     Horizontal,
     FourScreen,
+    /// All four nametables show the first one.
+    SingleScreenLower,
+    /// All four nametables show the second one.
+    SingleScreenUpper,
 }
 
 #[derive(Debug)]
 pub struct Rom {
     pub prg_rom: Vec<u8>,
     pub chr_rom: Vec<u8>,
-    pub mapper: u8,
+    pub mapper: u16,
+    /// The board variant within the mapper, only given by NES 2.0 headers.
+    pub submapper: u8,
     pub mirroring: Mirroring,
+    /// Bytes of PRG RAM at $6000-$7FFF, for the boards that have it.
+    pub prg_ram_size: usize,
 }
 
 #[derive(Debug)]
@@ -18,28 +26,66 @@ pub enum Error {
     UnsupportedVersion,
     InvalidHeader,
     InvalidMapper,
+    /// The board the header asks for is not emulated.
+    UnsupportedMapper(u16),
+    /// The file ends before the header or the ROM data it announces.
+    Truncated,
+    /// A NES 2.0 ROM size too large to address.
+    InvalidSize,
+    /// The PRG ROM, of this many bytes, is smaller than the bank the board
+    /// fixes at the end of the address space.
+    PrgRomTooSmall(usize),
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnsupportedVersion => write!(f, "unsupported iNES version"),
+            Error::InvalidHeader => write!(f, "invalid iNES header"),
+            Error::InvalidMapper => write!(f, "invalid mapper"),
+            Error::UnsupportedMapper(mapper) => write!(f, "unsupported mapper {}", mapper),
+            Error::Truncated => write!(f, "file is shorter than its header says"),
+            Error::InvalidSize => write!(f, "invalid NES 2.0 ROM size"),
+            Error::PrgRomTooSmall(len) => {
+                write!(f, "{} bytes of PRG ROM are too few for the mapper", len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 const NES_TAG: [u8; 4] = [0x4e, 0x45, 0x53, 0x1a];
+const HEADER_SIZE: usize = 16;
+const TRAINER_SIZE: usize = 512;
 
 const PRG_ROM_PAGE_SIZE: usize = 16 * 1024;
 const CHR_ROM_PAGE_SIZE: usize = 8 * 1024;
+const PRG_RAM_PAGE_SIZE: usize = 8 * 1024;
 
 impl Rom {
     pub fn new(raw: &[u8]) -> Result<Rom, Error> {
-        if raw[0..4] != NES_TAG {
+        let header = raw.get(..HEADER_SIZE).ok_or(Error::Truncated)?;
+        if header[0..4] != NES_TAG {
             return Err(Error::InvalidHeader);
         }
 
-        let mapper = (raw[6] >> 4) | (raw[7] & 0xf0);
-        let ines_version = (raw[7] >> 2) & 0x01;
+        let mut mapper = ((header[6] >> 4) | (header[7] & 0xf0)) as u16;
+        let ines_version = (header[7] >> 2) & 0x01;
 
         if ines_version != 0 {
             return Err(Error::UnsupportedVersion);
         }
 
-        let four_screen = (raw[6] & 0x08) != 0;
-        let vertical_screen = (raw[6] & 0x01) != 0;
+        let nes2 = header[7] & 0x0c == 0x08;
+        let mut submapper = 0;
+        if nes2 {
+            mapper |= ((header[8] & 0x0f) as u16) << 8;
+            submapper = header[8] >> 4;
+        }
+
+        let four_screen = (header[6] & 0x08) != 0;
+        let vertical_screen = (header[6] & 0x01) != 0;
 
         let mirroring = match (four_screen, vertical_screen) {
             (true, _) => Mirroring::FourScreen,
@@ -47,19 +93,114 @@ impl Rom {
             (false, false) => Mirroring::Horizontal,
         };
 
-        let prg_rom_size = raw[4] as usize * PRG_ROM_PAGE_SIZE;
-        let chr_rom_size = raw[5] as usize * CHR_ROM_PAGE_SIZE;
+        let (prg_rom_size, chr_rom_size, prg_ram_size) = if nes2 {
+            // volatile and battery backed RAM, each given as a shift count
+            let ram = |shift: u8| if shift == 0 { 0 } else { 64 << shift };
+            (
+                nes2_rom_size(header[4], header[9] & 0x0f, PRG_ROM_PAGE_SIZE)
+                    .ok_or(Error::InvalidSize)?,
+                nes2_rom_size(header[5], header[9] >> 4, CHR_ROM_PAGE_SIZE)
+                    .ok_or(Error::InvalidSize)?,
+                ram(header[10] & 0x0f) + ram(header[10] >> 4),
+            )
+        } else {
+            (
+                header[4] as usize * PRG_ROM_PAGE_SIZE,
+                header[5] as usize * CHR_ROM_PAGE_SIZE,
+                // 0 means 8K, for compatibility with headers from before the field
+                header[8].max(1) as usize * PRG_RAM_PAGE_SIZE,
+            )
+        };
 
-        let skip_trainer = (raw[6] & 0x04) != 0;
+        let skip_trainer = (header[6] & 0x04) != 0;
 
-        let prg_rom_start = 16 + if skip_trainer { 512 } else { 0 };
-        let chr_rom_start = prg_rom_start + prg_rom_size;
+        let prg_rom_start = HEADER_SIZE + if skip_trainer { TRAINER_SIZE } else { 0 };
+        let chr_rom_start = prg_rom_start
+            .checked_add(prg_rom_size)
+            .ok_or(Error::InvalidSize)?;
+        let chr_rom_end = chr_rom_start
+            .checked_add(chr_rom_size)
+            .ok_or(Error::InvalidSize)?;
+        let prg_rom = raw
+            .get(prg_rom_start..chr_rom_start)
+            .ok_or(Error::Truncated)?;
+        let chr_rom = raw
+            .get(chr_rom_start..chr_rom_end)
+            .ok_or(Error::Truncated)?;
 
         Ok(Self {
-            prg_rom: raw[prg_rom_start..(prg_rom_start + prg_rom_size)].to_vec(),
-            chr_rom: raw[chr_rom_start..(chr_rom_start + chr_rom_size)].to_vec(),
+            prg_rom: prg_rom.to_vec(),
+            chr_rom: chr_rom.to_vec(),
             mapper,
+            submapper,
             mirroring,
+            prg_ram_size,
         })
     }
 }
+
+/// A NES 2.0 ROM size from the LSB byte and the MSB nibble, which is either
+/// a count of `page_size` pages or, when the nibble is $F, an exponent and
+/// multiplier. `None` when the size does not fit in a `usize`.
+fn nes2_rom_size(lsb: u8, msb: u8, page_size: usize) -> Option<usize> {
+    if msb == 0x0f {
+        let exponent = (lsb >> 2) as u32;
+        let multiplier = (lsb & 0x03) as usize * 2 + 1;
+        1usize.checked_shl(exponent)?.checked_mul(multiplier)
+    } else {
+        ((msb as usize) << 8 | lsb as usize).checked_mul(page_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An iNES image with `prg` 16K and `chr` 8K pages.
+    fn image(prg: u8, chr: u8) -> Vec<u8> {
+        let mut raw = NES_TAG.to_vec();
+        raw.extend([prg, chr, 0x01, 0x00, 0, 0, 0, 0, 0, 0, 0, 0]);
+        raw.resize(
+            HEADER_SIZE + prg as usize * PRG_ROM_PAGE_SIZE + chr as usize * CHR_ROM_PAGE_SIZE,
+            0,
+        );
+        raw
+    }
+
+    #[test]
+    fn parses_an_ines_image() {
+        let rom = Rom::new(&image(2, 1)).unwrap();
+        assert_eq!(rom.prg_rom.len(), 2 * PRG_ROM_PAGE_SIZE);
+        assert_eq!(rom.chr_rom.len(), CHR_ROM_PAGE_SIZE);
+        assert_eq!(rom.mapper, 0);
+        assert_eq!(rom.mirroring, Mirroring::Vertical);
+    }
+
+    #[test]
+    fn files_shorter_than_their_header_says_are_truncated() {
+        assert!(matches!(Rom::new(&NES_TAG), Err(Error::Truncated)));
+
+        let mut raw = image(2, 1);
+        raw.pop();
+        assert!(matches!(Rom::new(&raw), Err(Error::Truncated)));
+
+        let mut raw = image(2, 1);
+        // a trainer that is not there
+        raw[6] |= 0x04;
+        assert!(matches!(Rom::new(&raw), Err(Error::Truncated)));
+    }
+
+    #[test]
+    fn nes2_exponent_sizes_are_checked() {
+        let mut raw = image(1, 0);
+        raw[7] = 0x08;
+        raw[9] = 0x0F;
+        // 2^14 * 1
+        raw[4] = 14 << 2;
+        assert_eq!(Rom::new(&raw).unwrap().prg_rom.len(), PRG_ROM_PAGE_SIZE);
+
+        // 2^63 * 7
+        raw[4] = 0xFF;
+        assert!(matches!(Rom::new(&raw), Err(Error::InvalidSize)));
+    }
+}
@@ -1,11 +1,35 @@
 use crate::{
-    cpu::{AddressingMode, Cpu},
-    instruction::{Size, INSTRUCTIONS},
+    cpu::{AddressingMode, Cpu, StopReason},
+    instruction::Size,
     mem::Mem,
+    Error,
 };
 
-pub fn trace(cpu: &Cpu) -> String {
-    let opcode = &INSTRUCTIONS[cpu.read_byte(cpu.pc) as usize];
+const DOTS_PER_SCANLINE: usize = 341;
+const SCANLINES_PER_FRAME: usize = 262;
+
+/// The line layouts `trace_with` can produce.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// The columns of nestest.log up to and including SP.
+    #[default]
+    Nestest,
+    /// All of the nestest.log columns, including the PPU scanline and dot
+    /// and the cumulative CPU cycle count.
+    NestestFull,
+    /// The layout of Mesen's trace logger, which has the flags as letters,
+    /// upper case when set, followed by the PPU position and frame count.
+    Mesen,
+}
+
+/// Formats the instruction at the PC and the CPU state before it executes in
+/// the layout of nestest.log, without the PPU and CYC columns.
+pub fn trace<M: Mem>(cpu: &Cpu<M>) -> Result<String, Error> {
+    trace_with(cpu, TraceFormat::Nestest)
+}
+
+pub fn trace_with<M: Mem>(cpu: &Cpu<M>, format: TraceFormat) -> Result<String, Error> {
+    let opcode = &cpu.variant.instructions()[cpu.read_byte(cpu.pc) as usize];
 
     let begin = cpu.pc;
     let mut hex_dump = vec![opcode.opcode];
@@ -13,7 +37,7 @@ pub fn trace(cpu: &Cpu) -> String {
     let (mem_addr, stored_value) = match opcode.mode {
         AddressingMode::None => (0, 0),
         _ => {
-            let addr = cpu.get_operand_address(&opcode.mode, begin + 1);
+            let (addr, _) = cpu.get_operand_address(&opcode.mode, begin.wrapping_add(1));
             (addr, cpu.read_byte(addr))
         }
     };
@@ -24,7 +48,7 @@ pub fn trace(cpu: &Cpu) -> String {
             _ => String::from(""),
         },
         Size::Two => {
-            let address = cpu.read_byte(begin + 1);
+            let address = cpu.read_byte(begin.wrapping_add(1));
             hex_dump.push(address);
 
             match opcode.mode {
@@ -52,25 +76,37 @@ pub fn trace(cpu: &Cpu) -> String {
                     mem_addr,
                     stored_value
                 ),
+                AddressingMode::ZeroPageIndirect => format!(
+                    "(${:02x}) = {:04x} = {:02x}",
+                    address, mem_addr, stored_value
+                ),
                 AddressingMode::None | AddressingMode::Relative => {
                     // assuming local jumps: BNE, BVS, etc....
-                    let address: usize =
-                        (begin as usize + 2).wrapping_add((address as i8) as usize);
+                    let address = begin.wrapping_add(2).wrapping_add((address as i8) as u16);
                     format!("${:04x}", address)
                 }
-                _ => panic!("unexpected addressing mode {:?}", opcode.mode),
+                _ => {
+                    return Err(Error::UnexpectedTraceMode {
+                        opcode: opcode.opcode,
+                        mode: opcode.mode,
+                    })
+                }
             }
         }
         Size::Three => {
-            let address_lo = cpu.read_byte(begin + 1);
-            let address_hi = cpu.read_byte(begin + 2);
+            let address_lo = cpu.read_byte(begin.wrapping_add(1));
+            let address_hi = cpu.read_byte(begin.wrapping_add(2));
             hex_dump.push(address_lo);
             hex_dump.push(address_hi);
 
-            let address = cpu.read_word(begin + 1);
+            let address = cpu.read_word(begin.wrapping_add(1));
 
             match opcode.mode {
                 AddressingMode::None => format!("${:04x}", address),
+                // jump targets are not dereferenced
+                AddressingMode::Absolute if matches!(opcode.opcode, 0x4c | 0x20) => {
+                    format!("${:04x}", address)
+                }
                 AddressingMode::Absolute => format!("${:04x} = {:02x}", mem_addr, stored_value),
                 AddressingMode::AbsoluteX => format!(
                     "${:04x},X @ {:04x} = {:02x}",
@@ -80,23 +116,23 @@ pub fn trace(cpu: &Cpu) -> String {
                     "${:04x},Y @ {:04x} = {:02x}",
                     address, mem_addr, stored_value
                 ),
-                AddressingMode::Indirect => {
-                    //jmp indirect
-                    let jmp_addr = if address & 0x00FF == 0x00FF {
-                        let lo = cpu.read_byte(address);
-                        let hi = cpu.read_byte(address & 0xFF00);
-                        (hi as u16) << 8 | (lo as u16)
-                    } else {
-                        cpu.read_word(address)
-                    };
-
-                    // let jmp_addr = cpu.read_byte_u16(address);
-                    format!("(${:04x}) = {:04x}", address, jmp_addr)
+                // jmp indirect, the operand address is the jump target
+                AddressingMode::Indirect => format!("(${:04x}) = {:04x}", address, mem_addr),
+                AddressingMode::AbsoluteIndexedIndirect => {
+                    format!("(${:04x},X) = {:04x}", address, mem_addr)
+                }
+                AddressingMode::ZeroPageRelative => {
+                    let target = begin
+                        .wrapping_add(3)
+                        .wrapping_add((address_hi as i8) as u16);
+                    format!("${:02x},${:04x}", address_lo, target)
+                }
+                _ => {
+                    return Err(Error::UnexpectedTraceMode {
+                        opcode: opcode.opcode,
+                        mode: opcode.mode,
+                    })
                 }
-                _ => panic!(
-                    "unexpected addressing mode {:?} has ops-len 3. code {:02x}",
-                    opcode.mode, opcode.opcode
-                ),
             }
         }
     };
@@ -106,21 +142,411 @@ pub fn trace(cpu: &Cpu) -> String {
         .map(|z| format!("{:02x}", z))
         .collect::<Vec<String>>()
         .join(" ");
-    let asm_str = format!(
-        "{:04x}  {:8} {: >4} {}",
-        begin, hex_str, opcode.mnemonic, tmp
-    )
-    .trim()
-    .to_string();
-
-    format!(
-        "{:47} A:{:02x} X:{:02x} Y:{:02x} P:{:02x} SP:{:02x}",
-        asm_str,
-        cpu.a,
-        cpu.x,
-        cpu.y,
-        u8::from(&cpu.status),
-        cpu.sp,
-    )
-    .to_ascii_uppercase()
+    // unofficial opcodes are marked by a * in place of the space before them
+    let mnemonic = if opcode.official {
+        format!(" {}", opcode.mnemonic)
+    } else {
+        format!("*{}", opcode.mnemonic)
+    };
+    let asm_str = format!("{:04x}  {:8} {} {}", begin, hex_str, mnemonic, tmp)
+        .trim()
+        .to_string();
+
+    let dots = cpu.cycles * 3;
+    let scanline = dots / DOTS_PER_SCANLINE % SCANLINES_PER_FRAME;
+    let dot = dots % DOTS_PER_SCANLINE;
+    let status = u8::from(&cpu.status);
+
+    let line = match format {
+        TraceFormat::Nestest | TraceFormat::NestestFull => {
+            let line = format!(
+                "{:47} A:{:02x} X:{:02x} Y:{:02x} P:{:02x} SP:{:02x}",
+                asm_str, cpu.a, cpu.x, cpu.y, status, cpu.sp,
+            )
+            .to_ascii_uppercase();
+            if format == TraceFormat::Nestest {
+                line
+            } else {
+                format!("{} PPU:{:>3},{:>3} CYC:{}", line, scanline, dot, cpu.cycles)
+            }
+        }
+        TraceFormat::Mesen => {
+            let flags: String = "NVUBDIZC"
+                .chars()
+                .enumerate()
+                .map(|(i, flag)| {
+                    if status & (0x80 >> i) != 0 {
+                        flag
+                    } else {
+                        flag.to_ascii_lowercase()
+                    }
+                })
+                .collect();
+            format!(
+                "{:47} A:{:02X} X:{:02X} Y:{:02X} P:{} SP:{:02X} CYC:{:>3} SL:{:>3} FC:{} CPU Cycle:{}",
+                asm_str.to_ascii_uppercase(),
+                cpu.a,
+                cpu.x,
+                cpu.y,
+                flags,
+                cpu.sp,
+                dot,
+                scanline,
+                dots / (DOTS_PER_SCANLINE * SCANLINES_PER_FRAME),
+                cpu.cycles,
+            )
+        }
+    };
+
+    Ok(line)
+}
+
+impl TraceFormat {
+    /// Guesses the format of a reference log from one of its lines.
+    pub fn detect(line: &str) -> TraceFormat {
+        if line.contains("CPU Cycle:") {
+            TraceFormat::Mesen
+        } else if line.contains("CYC:") {
+            TraceFormat::NestestFull
+        } else {
+            TraceFormat::Nestest
+        }
+    }
+}
+
+/// A field that differs between a reference line and the trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    pub name: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Where a trace first stopped matching a reference log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// The line number in the reference log, counting from 1.
+    pub line: usize,
+    pub expected: String,
+    pub actual: String,
+    pub fields: Vec<FieldDiff>,
+    /// The matching lines leading up to the divergence.
+    pub before: Vec<String>,
+    /// The reference lines following the divergence.
+    pub after: Vec<String>,
+    /// Why the CPU stopped before reaching the line, when it did.
+    pub stopped: Option<StopReason>,
+}
+
+impl std::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let first = self.line - self.before.len();
+        for (i, line) in self.before.iter().enumerate() {
+            writeln!(f, "{:>7}           {}", first + i, line)?;
+        }
+        writeln!(f, "{:>7} expected: {}", self.line, self.expected)?;
+        writeln!(f, "{:>7} actual:   {}", self.line, self.actual)?;
+
+        let marks: String = self
+            .expected
+            .chars()
+            .map(Some)
+            .chain(std::iter::repeat(None))
+            .zip(self.actual.chars().map(Some).chain(std::iter::repeat(None)))
+            .take(self.expected.len().max(self.actual.len()))
+            .map(|(expected, actual)| if expected == actual { ' ' } else { '^' })
+            .collect();
+        writeln!(f, "                  {}", marks.trim_end())?;
+
+        for (i, line) in self.after.iter().enumerate() {
+            writeln!(f, "{:>7}           {}", self.line + 1 + i, line)?;
+        }
+        for field in &self.fields {
+            writeln!(
+                f,
+                "{}: expected {}, got {}",
+                field.name, field.expected, field.actual
+            )?;
+        }
+        if let Some(stop) = self.stopped {
+            writeln!(f, "CPU stopped: {:?}", stop)?;
+        }
+        Ok(())
+    }
+}
+
+/// Width of the address, bytes and disassembly columns every format starts with.
+const DISASSEMBLY_WIDTH: usize = 48;
+
+/// Splits a trace line into named fields, the disassembly columns followed by
+/// the `NAME:value` register and timing columns.
+fn fields(line: &str) -> Vec<(String, String)> {
+    let split = line
+        .char_indices()
+        .nth(DISASSEMBLY_WIDTH)
+        .map_or(line.len(), |(i, _)| i);
+    let (disassembly, rest) = line.split_at(split);
+
+    let mut fields = vec![
+        (
+            "PC".to_string(),
+            disassembly.get(..4).unwrap_or("").to_string(),
+        ),
+        (
+            "bytes".to_string(),
+            disassembly.get(4..15).unwrap_or("").trim().to_string(),
+        ),
+        (
+            "instruction".to_string(),
+            disassembly.get(15..).unwrap_or("").trim().to_string(),
+        ),
+    ];
+
+    let rest = format!(" {}", rest);
+    let mut starts: Vec<(usize, &str)> = [
+        "A",
+        "X",
+        "Y",
+        "P",
+        "S",
+        "SP",
+        "PPU",
+        "CYC",
+        "SL",
+        "FC",
+        "CPU Cycle",
+    ]
+    .iter()
+    .filter_map(|name| rest.find(&format!(" {}:", name)).map(|i| (i, *name)))
+    .collect();
+    starts.sort();
+
+    for (i, (start, name)) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).map_or(rest.len(), |(end, _)| *end);
+        let value = &rest[start + name.len() + 2..end];
+        fields.push((name.to_string(), value.trim().to_string()));
+    }
+    fields
+}
+
+/// Steps `cpu` through the program, comparing the trace before every
+/// instruction with the lines of `reference`. Stops at the first line that
+/// differs, or at a line the CPU cannot get to because it jammed, returning
+/// up to `context` lines on either side of it, or returns `None` once every
+/// reference line has matched.
+pub fn diff<M: Mem>(
+    cpu: &mut Cpu<M>,
+    reference: &str,
+    format: TraceFormat,
+    context: usize,
+) -> Result<Option<Divergence>, Error> {
+    let reference: Vec<&str> = reference
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let mut before = std::collections::VecDeque::with_capacity(context);
+    let mut stopped = None;
+
+    for (i, expected) in reference.iter().enumerate() {
+        let actual = trace_with(cpu, format)?;
+
+        if actual != *expected || stopped.is_some() {
+            let actual_fields = fields(&actual);
+            let fields = fields(expected)
+                .into_iter()
+                .filter_map(|(name, expected)| {
+                    let actual = actual_fields
+                        .iter()
+                        .find(|(other, _)| *other == name)
+                        .map_or("", |(_, value)| value.as_str());
+                    (expected != actual).then(|| FieldDiff {
+                        name,
+                        actual: actual.to_string(),
+                        expected,
+                    })
+                })
+                .collect();
+
+            return Ok(Some(Divergence {
+                line: i + 1,
+                expected: expected.to_string(),
+                actual,
+                fields,
+                before: before.into(),
+                after: reference[i + 1..]
+                    .iter()
+                    .take(context)
+                    .map(|line| line.to_string())
+                    .collect(),
+                stopped,
+            }));
+        }
+
+        if context > 0 {
+            if before.len() == context {
+                before.pop_front();
+            }
+            before.push_back(actual);
+        }
+        // BRK carries on into its handler, anything else `step` stops at
+        // leaves the CPU unable to follow the rest of the log
+        match cpu.step()? {
+            None | Some(StopReason::Brk(_)) => (),
+            Some(stop) => stopped = Some(stop),
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mem::Ram;
+
+    fn cpu_at(pc: u16, bytes: &[u8]) -> Cpu<Ram> {
+        let mut ram = Ram::new();
+        ram.load(pc, bytes);
+        let mut cpu = Cpu::new(ram);
+        cpu.pc = pc;
+        cpu
+    }
+
+    #[test]
+    fn operands_wrap_around_the_end_of_memory() {
+        let cpu = cpu_at(0xFFFE, &[0xAD, 0x34, 0x12]);
+        let line = trace(&cpu).unwrap();
+        assert!(
+            line.starts_with("FFFE  AD 34 12  LDA $1234 = 00"),
+            "{}",
+            line
+        );
+    }
+
+    #[test]
+    fn branch_targets_wrap_around_the_end_of_memory() {
+        let cpu = cpu_at(0xFFFE, &[0xD0, 0x04]);
+        let line = trace(&cpu).unwrap();
+        assert!(line.starts_with("FFFE  D0 04     BNE $0004"), "{}", line);
+    }
+
+    /// Traces the first `lines` instructions of `bytes` run from $0400.
+    fn reference(bytes: &[u8], lines: usize) -> Vec<String> {
+        let mut cpu = cpu_at(0x0400, bytes);
+        (0..lines)
+            .map(|_| {
+                let line = trace(&cpu).unwrap();
+                cpu.step().unwrap();
+                line
+            })
+            .collect()
+    }
+
+    // lda #$01, ldx #$02, nop
+    const PROGRAM: [u8; 5] = [0xA9, 0x01, 0xA2, 0x02, 0xEA];
+
+    #[test]
+    fn diff_matches_an_identical_log() {
+        let log = reference(&PROGRAM, 3).join("\n");
+        let mut cpu = cpu_at(0x0400, &PROGRAM);
+        assert_eq!(diff(&mut cpu, &log, TraceFormat::Nestest, 2).unwrap(), None);
+        assert_eq!(cpu.pc, 0x0405);
+    }
+
+    #[test]
+    fn diff_reports_the_first_differing_line_and_field() {
+        let mut log = reference(&PROGRAM, 3);
+        let expected = log[1].replace("A:01", "A:02");
+        log[1] = expected.clone();
+
+        let mut cpu = cpu_at(0x0400, &PROGRAM);
+        let divergence = diff(&mut cpu, &log.join("\n"), TraceFormat::Nestest, 2)
+            .unwrap()
+            .unwrap();
+        assert_eq!(divergence.line, 2);
+        assert_eq!(divergence.expected, expected);
+        assert!(divergence.actual.contains("A:01"), "{}", divergence.actual);
+        assert_eq!(
+            divergence.fields,
+            [FieldDiff {
+                name: "A".to_string(),
+                expected: "02".to_string(),
+                actual: "01".to_string(),
+            }]
+        );
+        assert_eq!(divergence.before, log[..1]);
+        assert_eq!(divergence.after, log[2..]);
+        assert_eq!(divergence.stopped, None);
+    }
+
+    #[test]
+    fn diff_stops_where_a_short_log_ends() {
+        let log = reference(&PROGRAM, 1).join("\n");
+        let mut cpu = cpu_at(0x0400, &PROGRAM);
+        assert_eq!(diff(&mut cpu, &log, TraceFormat::Nestest, 2).unwrap(), None);
+        assert_eq!(cpu.pc, 0x0402);
+    }
+
+    #[test]
+    fn diff_reports_a_jam_before_the_log_ends() {
+        // lda #$01, jam
+        let bytes = [0xA9, 0x01, 0x02];
+        let mut log = reference(&bytes, 2);
+        // the jammed CPU would trace the same line again
+        log.push(log[1].clone());
+
+        let mut cpu = cpu_at(0x0400, &bytes);
+        let divergence = diff(&mut cpu, &log.join("\n"), TraceFormat::Nestest, 2)
+            .unwrap()
+            .unwrap();
+        assert_eq!(divergence.line, 3);
+        assert_eq!(
+            divergence.stopped,
+            Some(StopReason::Jammed(crate::cpu::Jam {
+                opcode: 0x02,
+                addr: 0x0402,
+            }))
+        );
+        assert!(divergence.fields.is_empty());
+    }
+
+    /// The first line of nestest.log.
+    const NESTEST_FULL: &str = "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7";
+    const MESEN: &str = "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:nvUbdIzc SP:FD CYC: 21 SL:  0 FC:0 CPU Cycle:7";
+
+    #[test]
+    fn nestest_lines_match_nestest_log() {
+        let cpu = cpu_at(0xC000, &[0x4C, 0xF5, 0xC5]);
+        assert_eq!(
+            trace_with(&cpu, TraceFormat::NestestFull).unwrap(),
+            NESTEST_FULL
+        );
+        assert_eq!(
+            trace(&cpu).unwrap(),
+            NESTEST_FULL[..NESTEST_FULL.find(" PPU:").unwrap()]
+        );
+    }
+
+    #[test]
+    fn mesen_lines_have_letter_flags_and_the_ppu_position() {
+        let mut cpu = cpu_at(0xC000, &[0x4C, 0xF5, 0xC5]);
+        assert_eq!(trace_with(&cpu, TraceFormat::Mesen).unwrap(), MESEN);
+
+        cpu.status.negative = true;
+        cpu.status.carry = true;
+        cpu.cycles = 100_000;
+        assert_eq!(
+            trace_with(&cpu, TraceFormat::Mesen).unwrap(),
+            "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:NvUbdIzC SP:FD CYC:261 SL: 93 FC:3 CPU Cycle:100000"
+        );
+    }
+
+    #[test]
+    fn detect_recognises_each_format() {
+        assert_eq!(TraceFormat::detect(NESTEST_FULL), TraceFormat::NestestFull);
+        assert_eq!(TraceFormat::detect(MESEN), TraceFormat::Mesen);
+        let nestest = &NESTEST_FULL[..NESTEST_FULL.find(" PPU:").unwrap()];
+        assert_eq!(TraceFormat::detect(nestest), TraceFormat::Nestest);
+    }
 }
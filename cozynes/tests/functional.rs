@@ -0,0 +1,45 @@
+//! Runs Klaus Dormann's 6502 functional test on a flat 64K machine. The
+//! prebuilt `tests/roms/6502_functional_test.bin` comes from the r6502 crate
+//! (Apache-2.0 OR MIT).
+
+use std::path::Path;
+
+use cozynes::{
+    cpu::{Cpu, StopReason, Variant},
+    mem::Ram,
+};
+
+/// Where the prebuilt binary is loaded and starts.
+const ORIGIN: u16 = 0x0000;
+const START: u16 = 0x0400;
+/// The trap the prebuilt binary jumps to once every test passed.
+const SUCCESS: u16 = 0x3469;
+/// Holds the number of the test being run.
+const TEST_CASE: u16 = 0x0200;
+
+#[test]
+fn functional() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/roms/6502_functional_test.bin");
+    let bin = std::fs::read(&path).unwrap_or_else(|error| panic!("{}: {}", path.display(), error));
+
+    let mut ram = Ram::new();
+    ram.load(ORIGIN, &bin);
+    let mut cpu = Cpu::new(ram);
+    // the test covers decimal mode, which the 2A03 lacks
+    cpu.variant = Variant::Nmos6502;
+    cpu.pc = START;
+
+    // the test exercises BRK, run hands control back on it so resume
+    let reason = loop {
+        match cpu.run_until_trap().unwrap() {
+            StopReason::Brk(_) => continue,
+            reason => break reason,
+        }
+    };
+    if reason != StopReason::Trapped(SUCCESS) {
+        panic!(
+            "functional test failed in test {:#04x}: {:?}, {}",
+            cpu.bus.data[TEST_CASE as usize], reason, cpu
+        );
+    }
+}
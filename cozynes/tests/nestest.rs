@@ -0,0 +1,103 @@
+//! Runs nestest in automation mode and compares the CPU state before every
+//! instruction against a reference trace. `tests/roms/nestest.nes` and the
+//! trace in `tests/roms/nestest.txt` come from the tetanes-core crate
+//! (MIT OR Apache-2.0). The trace is in that emulator's own layout, with the
+//! flags sometimes written as letters, so the registers and cycle count are
+//! parsed out of it instead of going through `trace::diff`.
+
+use std::path::Path;
+
+use cozynes::{
+    bus::Bus,
+    cpu::Cpu,
+    error::{FaultPolicy, Policy},
+    mem::Mem,
+    rom::Rom,
+};
+
+/// The CPU state at the start of one line of the reference trace.
+#[derive(Debug, PartialEq, Eq)]
+struct State {
+    pc: u16,
+    a: u8,
+    x: u8,
+    y: u8,
+    p: u8,
+    sp: u8,
+    cycles: usize,
+}
+
+fn field<'a>(line: &'a str, name: &str) -> &'a str {
+    let start = line
+        .find(&format!(" {}:", name))
+        .unwrap_or_else(|| panic!("no {} in {:?}", name, line))
+        + name.len()
+        + 2;
+    line[start..].split_whitespace().next().unwrap_or("")
+}
+
+fn parse(line: &str) -> State {
+    let hex = |value: &str| u8::from_str_radix(value, 16).unwrap();
+    let p = field(line, "P");
+    // flags are either two hex digits or NVUBDIZC, upper case when set
+    let p = if p.len() == 8 {
+        p.chars()
+            .enumerate()
+            .filter(|(_, flag)| flag.is_ascii_uppercase())
+            .fold(0, |p, (i, _)| p | 0x80 >> i)
+    } else {
+        hex(p)
+    };
+    State {
+        pc: u16::from_str_radix(&line[1..5], 16).unwrap(),
+        a: hex(field(line, "A")),
+        x: hex(field(line, "X")),
+        y: hex(field(line, "Y")),
+        p,
+        sp: hex(field(line, "SP")),
+        cycles: field(line, "CYC").parse().unwrap(),
+    }
+}
+
+#[test]
+fn nestest() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/roms");
+    let read = |name: &str| {
+        let path = dir.join(name);
+        std::fs::read(&path).unwrap_or_else(|error| panic!("{}: {}", path.display(), error))
+    };
+    let rom = read("nestest.nes");
+    let log = String::from_utf8(read("nestest.txt")).unwrap();
+
+    let mut cpu = Cpu::new(Bus::new(Rom::new(&rom).unwrap()).unwrap());
+    cpu.fault_policy = FaultPolicy {
+        unimplemented_hardware: Policy::Ignore,
+        ..FaultPolicy::default()
+    };
+    // automation mode skips the menu that needs a PPU
+    cpu.pc = 0xC000;
+
+    for (i, line) in log.lines().filter(|line| !line.is_empty()).enumerate() {
+        let actual = State {
+            pc: cpu.pc,
+            a: cpu.a,
+            x: cpu.x,
+            y: cpu.y,
+            p: u8::from(&cpu.status),
+            sp: cpu.sp,
+            cycles: cpu.cycles,
+        };
+        assert_eq!(
+            actual,
+            parse(line),
+            "nestest diverges at line {}: {}",
+            i + 1,
+            line
+        );
+        cpu.step().unwrap();
+    }
+
+    // nestest leaves the number of the first failed test in $02 and $03
+    assert_eq!(cpu.read_byte(0x0002), 0x00, "official opcode test failed");
+    assert_eq!(cpu.read_byte(0x0003), 0x00, "unofficial opcode test failed");
+}
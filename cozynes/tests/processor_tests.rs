@@ -0,0 +1,228 @@
+//! Runs the single step tests from the ProcessorTests suite against every
+//! opcode. Each test gives the state before and after one instruction and
+//! the bus access made on every cycle. The suite is not distributed with the
+//! crate, so the test is ignored by default. Put its `nes6502`, `6502` and
+//! `wdc65c02` directories in `tests/processor_tests`, or point
+//! `PROCESSOR_TESTS` at the directory holding them, and run it with
+//! `cargo test --release --test processor_tests -- --ignored`.
+
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+};
+
+use cozynes::{
+    cpu::{Cpu, Variant},
+    error::{FaultPolicy, Policy},
+    mem::Mem,
+};
+use serde::Deserialize;
+
+/// Instructions that halt the CPU, the suite records them running forever.
+const HALTING: [&str; 3] = ["JAM", "STP", "WAI"];
+
+/// How many mismatches are printed for each opcode.
+const REPORTED: usize = 3;
+
+#[derive(Deserialize)]
+struct Test {
+    name: String,
+    initial: State,
+    #[serde(rename = "final")]
+    expected: State,
+    cycles: Vec<(u16, u8, String)>,
+}
+
+#[derive(Deserialize)]
+struct State {
+    pc: u16,
+    s: u8,
+    a: u8,
+    x: u8,
+    y: u8,
+    p: u8,
+    ram: Vec<(u16, u8)>,
+}
+
+/// 64K of RAM that records every access.
+struct Recorder {
+    ram: Vec<u8>,
+    accesses: RefCell<Vec<(u16, u8, String)>>,
+}
+
+impl Mem for Recorder {
+    fn read_byte(&self, addr: u16) -> u8 {
+        let value = self.ram[addr as usize];
+        self.accesses
+            .borrow_mut()
+            .push((addr, value, "read".to_string()));
+        value
+    }
+
+    fn write_byte(&mut self, addr: u16, value: u8) {
+        self.ram[addr as usize] = value;
+        self.accesses
+            .borrow_mut()
+            .push((addr, value, "write".to_string()));
+    }
+}
+
+fn setup(variant: Variant, state: &State) -> Cpu<Recorder> {
+    let mut cpu = Cpu::new(Recorder {
+        ram: vec![0; 0x10000],
+        accesses: RefCell::new(Vec::new()),
+    });
+    cpu.variant = variant;
+    cpu.fault_policy = FaultPolicy::all(Policy::Ignore);
+    cpu.pc = state.pc;
+    cpu.sp = state.s;
+    cpu.a = state.a;
+    cpu.x = state.x;
+    cpu.y = state.y;
+    cpu.status = state.p.into();
+    for &(addr, value) in &state.ram {
+        cpu.bus.ram[addr as usize] = value;
+    }
+    cpu.bus.accesses.borrow_mut().clear();
+    cpu
+}
+
+/// Runs one test, returning a description of everything that differs.
+fn run(variant: Variant, test: &Test) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    let expected = &test.expected;
+
+    let mut cpu = setup(variant, &test.initial);
+    let cycles = cpu.cycles;
+    cpu.step().unwrap();
+
+    // B and the unused bit are not stored in the register
+    let p = u8::from(&cpu.status);
+    for (name, actual, expected) in [
+        ("pc", cpu.pc, expected.pc),
+        ("s", cpu.sp as u16, expected.s as u16),
+        ("a", cpu.a as u16, expected.a as u16),
+        ("x", cpu.x as u16, expected.x as u16),
+        ("y", cpu.y as u16, expected.y as u16),
+        ("p", (p & 0xCF) as u16, (expected.p & 0xCF) as u16),
+    ] {
+        if actual != expected {
+            mismatches.push(format!(
+                "{} is {:#x}, expected {:#x}",
+                name, actual, expected
+            ));
+        }
+    }
+    for &(addr, value) in &expected.ram {
+        let actual = cpu.bus.ram[addr as usize];
+        if actual != value {
+            mismatches.push(format!(
+                "${:04X} is {:#04x}, expected {:#04x}",
+                addr, actual, value
+            ));
+        }
+    }
+    if cpu.cycles - cycles != test.cycles.len() {
+        mismatches.push(format!(
+            "took {} cycles, expected {}",
+            cpu.cycles - cycles,
+            test.cycles.len()
+        ));
+    }
+
+    // `tick` runs the 65C02 a whole instruction at a time, without its
+    // dummy accesses
+    if !variant.is_cmos() {
+        let mut cpu = setup(variant, &test.initial);
+        cpu.tick().unwrap();
+        while !cpu.at_instruction_boundary() {
+            cpu.tick().unwrap();
+        }
+        let accesses = cpu.bus.accesses.into_inner();
+        if accesses != test.cycles {
+            mismatches.push(format!(
+                "bus activity is {:?}, expected {:?}",
+                accesses, test.cycles
+            ));
+        }
+    }
+
+    mismatches
+}
+
+fn run_suite(variant: Variant, dir: &Path) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    for ins in variant.instructions().iter() {
+        if HALTING.contains(&ins.mnemonic) {
+            continue;
+        }
+
+        let path = dir.join(format!("{:02x}.json", ins.opcode));
+        let Ok(json) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let tests: Vec<Test> = serde_json::from_str(&json)
+            .unwrap_or_else(|err| panic!("invalid test file {}: {}", path.display(), err));
+
+        let mut failed = 0;
+        let mut reports = Vec::new();
+        for test in &tests {
+            let mismatches = run(variant, test);
+            if mismatches.is_empty() {
+                continue;
+            }
+            failed += 1;
+            if reports.len() < REPORTED {
+                reports.push(format!("  {}: {}", test.name, mismatches.join(", ")));
+            }
+        }
+
+        if failed > 0 {
+            failures.push(format!(
+                "{:?} {:02X} {}: {} of {} tests failed\n{}",
+                variant,
+                ins.opcode,
+                ins.mnemonic,
+                failed,
+                tests.len(),
+                reports.join("\n")
+            ));
+        }
+    }
+
+    failures
+}
+
+#[test]
+#[ignore = "needs the ProcessorTests suite, see the module docs"]
+fn processor_tests() {
+    let root = std::env::var_os("PROCESSOR_TESTS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/processor_tests"));
+
+    let mut ran = false;
+    let mut failures = Vec::new();
+    for (name, variant) in [
+        ("nes6502", Variant::Ricoh2A03),
+        ("6502", Variant::Nmos6502),
+        ("wdc65c02", Variant::Wdc65C02),
+    ] {
+        // the suite keeps its files in a directory per version
+        let dir = root.join(name);
+        let dir = if dir.join("v1").is_dir() {
+            dir.join("v1")
+        } else {
+            dir
+        };
+        if !dir.is_dir() {
+            continue;
+        }
+        ran = true;
+        failures.extend(run_suite(variant, &dir));
+    }
+
+    assert!(ran, "no test suites found in {}", root.display());
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}